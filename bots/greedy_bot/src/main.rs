@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 
 use clap::Parser;
-use gomori::{Board, Card, CardToPlay, CardsSet, Color, Field, PlayTurnResponse, Rank};
+use gomori::{Board, Card, CardToPlay, CardsSet, Color, PlayTurnResponse, Rank};
 use gomori_bot_utils::Bot;
 use rand::rngs::StdRng;
 use rand::{seq::SliceRandom, SeedableRng};
@@ -80,6 +80,14 @@ impl GreedyBot {
 }
 
 impl Bot for GreedyBot {
+    fn name(&self) -> String {
+        String::from(env!("CARGO_PKG_NAME"))
+    }
+
+    fn version(&self) -> String {
+        String::from(env!("CARGO_PKG_VERSION"))
+    }
+
     fn new_game(&mut self, _color: Color) {}
 
     fn play_first_turn(&mut self, cards: [Card; 5]) -> Card {
@@ -89,12 +97,11 @@ impl Bot for GreedyBot {
     fn play_turn(
         &mut self,
         cards: [Card; 5],
-        fields: Vec<Field>,
+        mut board: Board,
         _cards_won_by_opponent: CardsSet,
     ) -> PlayTurnResponse {
         let mut cards_to_play = vec![];
 
-        let mut board = Board::new(&fields);
         let mut remaining_cards: BTreeSet<Card> = BTreeSet::from(cards);
 
         while let Some(card_to_play) = self.best_card_placement(&board, &remaining_cards) {
@@ -1,2 +0,0 @@
-mod game_state;
-pub use game_state::*;
@@ -1,8 +1,7 @@
-use gomori::{Card, CardToPlay, CardsSet, Color, Field, PlayTurnResponse, Rank};
+use gomori::{legal_turns, Board, Card, CardToPlay, CardsSet, Color, PlayTurnResponse, Rank};
 use gomori_bot_utils::Bot;
 
 use clap::Parser;
-use max_bot::GameState;
 use tracing::debug;
 use tracing_subscriber::filter::{LevelFilter, Targets};
 use tracing_subscriber::layer::SubscriberExt;
@@ -41,6 +40,14 @@ fn initialize_logging(level: LevelFilter) {
 }
 
 impl Bot for DFSBot {
+    fn name(&self) -> String {
+        String::from(env!("CARGO_PKG_NAME"))
+    }
+
+    fn version(&self) -> String {
+        String::from(env!("CARGO_PKG_VERSION"))
+    }
+
     fn new_game(&mut self, _color: Color) {}
 
     fn play_first_turn(&mut self, cards: [Card; 5]) -> Card {
@@ -56,82 +63,31 @@ impl Bot for DFSBot {
         cards[0]
     }
 
-    fn play_turn(&mut self, cards: [Card; 5], fields: Vec<Field>, _: CardsSet) -> PlayTurnResponse {
-        let root = GameState::initial(cards, fields);
-        let cards_to_play = search_unroll(&root);
-        PlayTurnResponse(cards_to_play)
-    }
-}
-
-fn search_unroll(state0: &GameState) -> Vec<CardToPlay> {
-    let mut best_score: i8 = i8::MIN;
-    let mut best_actions = [None, None, None, None, None];
-    for action0 in state0.possible_actions() {
-        let state1 = state0.apply_action(action0);
-        if state1.is_terminal() {
-            if state1.score_delta > best_score {
-                best_score = state1.score_delta;
-                best_actions = [Some(action0), None, None, None, None];
-                debug!("New best score with action0 {:?}", action0);
-            }
-            continue;
-        }
-        for action1 in state1.possible_actions() {
-            let state2 = state1.apply_action(action1);
-            if state2.is_terminal() {
-                if state2.score_delta > best_score {
-                    best_score = state2.score_delta;
-                    best_actions = [Some(action0), Some(action1), None, None, None];
-                    debug!("New best score with action1 {:?}", action1);
-                }
-                continue;
-            }
-            for action2 in state2.possible_actions() {
-                let state3 = state2.apply_action(action2);
-                if state3.is_terminal() {
-                    if state3.score_delta > best_score {
-                        best_score = state3.score_delta;
-                        best_actions = [Some(action0), Some(action1), Some(action2), None, None];
-                        debug!("New best score with action2 {:?}", action2);
-                    }
-                    continue;
-                }
-                for action3 in state3.possible_actions() {
-                    let state4 = state3.apply_action(action3);
-                    if state4.is_terminal() {
-                        if state4.score_delta > best_score {
-                            best_score = state4.score_delta;
-                            best_actions = [
-                                Some(action0),
-                                Some(action1),
-                                Some(action2),
-                                Some(action3),
-                                None,
-                            ];
-                            debug!("New best score with action3 {:?}", action3);
-                        }
-                        continue;
-                    }
-                    for action4 in state4.possible_actions() {
-                        let state5 = state4.apply_action(action4);
-                        if state5.is_terminal() {
-                            if state5.score_delta >= best_score {
-                                best_score = state5.score_delta;
-                                best_actions = [
-                                    Some(action0),
-                                    Some(action1),
-                                    Some(action2),
-                                    Some(action3),
-                                    Some(action4),
-                                ];
-                                debug!("New best score with action4 {:?}", best_actions);
-                            }
-                            continue;
-                        }
-                    }
-                }
+    fn play_turn(&mut self, cards: [Card; 5], board: Board, _: CardsSet) -> PlayTurnResponse {
+        let mut best_score = None;
+        let mut best_turn = Vec::new();
+        for turn in legal_turns(&board, CardsSet::from_iter(cards)).deduplicate_positions() {
+            let score = score_turn(&board, &turn);
+            // Ties go to the earlier turn, except that a later turn playing all five cards wins
+            let is_better = match best_score {
+                None => true,
+                Some(best_score) if turn.len() == 5 => score >= best_score,
+                Some(best_score) => score > best_score,
+            };
+            if is_better {
+                debug!("New best score {} with {:?}", score, turn);
+                best_score = Some(score);
+                best_turn = turn;
             }
         }
+        PlayTurnResponse(best_turn)
     }
-    best_actions.into_iter().filter_map(|x| x).collect()
+}
+
+/// The number of cards won by a turn.
+fn score_turn(board: &Board, turn: &[CardToPlay]) -> u32 {
+    let mut board = board.clone();
+    turn.iter()
+        .map(|ctp| board.make(ctp).unwrap().cards_won.len())
+        .sum()
 }
@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 
 use clap::Parser;
-use gomori::{Board, Card, CardToPlay, CardsSet, Color, Field, PlayTurnResponse, Rank};
+use gomori::{Board, Card, CardToPlay, CardsSet, Color, PlayTurnResponse, Rank};
 use gomori_bot_utils::Bot;
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 
@@ -38,6 +38,14 @@ fn possible_card_placements(board: &Board, cards: &BTreeSet<Card>) -> Vec<(i8, i
 }
 
 impl Bot for RandomBot {
+    fn name(&self) -> String {
+        String::from(env!("CARGO_PKG_NAME"))
+    }
+
+    fn version(&self) -> String {
+        String::from(env!("CARGO_PKG_VERSION"))
+    }
+
     fn new_game(&mut self, _color: Color) {}
 
     fn play_first_turn(&mut self, cards: [Card; 5]) -> Card {
@@ -47,12 +55,11 @@ impl Bot for RandomBot {
     fn play_turn(
         &mut self,
         cards: [Card; 5],
-        fields: Vec<Field>,
+        mut board: Board,
         _cards_won_by_opponent: CardsSet,
     ) -> PlayTurnResponse {
         let mut cards_to_play = vec![];
 
-        let mut board = Board::new(&fields);
         let mut remaining_cards: BTreeSet<Card> = BTreeSet::from(cards);
         while let Some((i, j, card)) =
             possible_card_placements(&board, &remaining_cards).choose(&mut self.rng)
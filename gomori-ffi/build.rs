@@ -0,0 +1,14 @@
+// Generates the C header for the library into OUT_DIR.
+//
+// The header in the include directory is a copy of it, which is kept up to date by the
+// header_is_up_to_date test.
+
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    cbindgen::generate(&crate_dir)
+        .expect("Unable to generate C bindings")
+        .write_to_file(format!("{}/gomori.h", out_dir));
+}
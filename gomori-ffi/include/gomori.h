@@ -0,0 +1,212 @@
+#ifndef GOMORI_H
+#define GOMORI_H
+
+/* This file is generated by cbindgen from gomori-ffi/src/lib.rs, do not edit it. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of a function call.
+typedef enum GomoriStatus {
+  GOMORI_STATUS_OK = 0,
+  // A required pointer argument was null.
+  GOMORI_STATUS_NULL_POINTER,
+  // A card has an invalid suit or rank, or a set of cards has bits above bit 51.
+  GOMORI_STATUS_INVALID_CARD,
+  // The fields do not form a valid board, e.g. because they don't fit into a 4 x 4 area.
+  GOMORI_STATUS_INVALID_BOARD,
+  // The card cannot be played at that position.
+  GOMORI_STATUS_ILLEGAL_CARD_PLAYED,
+  // The turn is illegal, use `gomori_validate_turn()` to find out why.
+  GOMORI_STATUS_ILLEGAL_MOVE,
+  // An internal error occurred. This is a bug in the library.
+  GOMORI_STATUS_PANIC,
+} GomoriStatus;
+
+// The kind of a rule violation in a turn.
+typedef enum GomoriViolationKind {
+  GOMORI_VIOLATION_KIND_PLAYED_CARD_NOT_IN_HAND,
+  GOMORI_VIOLATION_KIND_PLAYED_ZERO_CARDS,
+  GOMORI_VIOLATION_KIND_PLAYED_MORE_THAN_FIVE_CARDS,
+  GOMORI_VIOLATION_KIND_ILLEGAL_CARD_PLAYED,
+  GOMORI_VIOLATION_KIND_PLAYED_CARD_AFTER_END_OF_COMBO,
+  GOMORI_VIOLATION_KIND_PREMATURELY_ENDED_COMBO,
+} GomoriViolationKind;
+
+// A board with at least one card on it.
+typedef struct GomoriBoard GomoriBoard;
+
+// A card, with the suit `0` to `3` meaning ♦, ♥, ♠ and ♣, and the rank `0` to `12` meaning
+// 2, 3, ..., 10, J, Q, K, A.
+typedef struct GomoriCard {
+  uint8_t suit;
+  uint8_t rank;
+} GomoriCard;
+
+// A set of cards, where the card with suit `s` and rank `r` is bit `4 * r + s`.
+typedef uint64_t GomoriCardsSet;
+
+// A field on the board.
+typedef struct GomoriField {
+  int8_t i;
+  int8_t j;
+  // Whether there is a face-up card on top of the field.
+  bool has_top_card;
+  // The face-up card, only meaningful if `has_top_card` is set.
+  struct GomoriCard top_card;
+  // The cards below the top card, or all cards if there is no face-up card.
+  GomoriCardsSet hidden_cards;
+} GomoriField;
+
+typedef struct GomoriCoordinates {
+  int8_t i;
+  int8_t j;
+} GomoriCoordinates;
+
+// A card to be placed on the board.
+typedef struct GomoriCardToPlay {
+  struct GomoriCard card;
+  int8_t i;
+  int8_t j;
+  // Whether there is a target for the ability of a king.
+  bool has_target;
+  // The target for the ability of a king, only meaningful if `has_target` is set.
+  int8_t target_i;
+  int8_t target_j;
+} GomoriCardToPlay;
+
+// The effects of placing a single card.
+typedef struct GomoriEffects {
+  // The cards that were won by placing the card.
+  GomoriCardsSet cards_won;
+  // Whether another card must be played.
+  bool combo;
+} GomoriEffects;
+
+// A rule violation in a turn, see `gomori_validate_turn()`.
+typedef struct GomoriTurnViolation {
+  enum GomoriViolationKind kind;
+  // The index of the offending card in the turn, or -1 if the violation is about the whole turn.
+  int32_t card_idx;
+} GomoriTurnViolation;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a board from a list of fields.
+//
+// On success, `*out_board` is set to a new board, which must be freed with `gomori_board_free()`.
+//
+// # Safety
+//
+// `fields` must point to `num_fields` fields, and `out_board` must be valid for writes.
+enum GomoriStatus gomori_board_new(const struct GomoriField *fields,
+                                   size_t num_fields,
+                                   struct GomoriBoard **out_board);
+
+// Creates a copy of the board, which must be freed with `gomori_board_free()`.
+//
+// Returns null if `board` is null.
+//
+// # Safety
+//
+// `board` must be null or a valid board.
+struct GomoriBoard *gomori_board_clone(const struct GomoriBoard *board);
+
+// Frees a board. Does nothing if `board` is null.
+//
+// # Safety
+//
+// `board` must be null or a valid board, which must not be used afterwards.
+void gomori_board_free(struct GomoriBoard *board);
+
+// Writes the fields of the board, sorted by their coordinates, to `out_fields`.
+//
+// # Safety
+//
+// `board` must be a valid board, `out_fields` must be valid for `capacity` writes, and
+// `out_num_fields` must be valid for writes.
+enum GomoriStatus gomori_board_fields(const struct GomoriBoard *board,
+                                      struct GomoriField *out_fields,
+                                      size_t capacity,
+                                      size_t *out_num_fields);
+
+// Writes the coordinates where the card can be placed to `out_coordinates`.
+//
+// There are at most 49 such coordinates. Kings that are placed on another card need a target
+// for their ability, see `Board::locations_for_card` in the Rust documentation.
+//
+// # Safety
+//
+// `board` must be a valid board, `out_coordinates` must be valid for `capacity` writes, and
+// `out_num_coordinates` must be valid for writes.
+enum GomoriStatus gomori_board_locations_for_card(const struct GomoriBoard *board,
+                                                  struct GomoriCard card,
+                                                  struct GomoriCoordinates *out_coordinates,
+                                                  size_t capacity,
+                                                  size_t *out_num_coordinates);
+
+// Calculates the effects of placing a card, without modifying the board.
+//
+// Returns `GOMORI_STATUS_ILLEGAL_CARD_PLAYED` if the card cannot be placed there.
+//
+// # Safety
+//
+// `board` and `card_to_play` must be valid, and `out_effects` must be valid for writes.
+enum GomoriStatus gomori_board_calculate(const struct GomoriBoard *board,
+                                         const struct GomoriCardToPlay *card_to_play,
+                                         struct GomoriEffects *out_effects);
+
+// Places a card on the board, modifying it in place.
+//
+// Returns `GOMORI_STATUS_ILLEGAL_CARD_PLAYED` and leaves the board unchanged if the card
+// cannot be placed there. `out_effects` may be null if the effects are not needed.
+//
+// # Safety
+//
+// `board` and `card_to_play` must be valid, and `out_effects` must be null or valid for writes.
+enum GomoriStatus gomori_board_play_card(struct GomoriBoard *board,
+                                         const struct GomoriCardToPlay *card_to_play,
+                                         struct GomoriEffects *out_effects);
+
+// Finds all rule violations in a turn with the given hand of five cards, and writes them
+// to `out_violations`. A legal turn has no violations.
+//
+// # Safety
+//
+// `board` must be a valid board, `hand` must point to 5 cards, `cards` must point to
+// `num_cards` cards, `out_violations` must be valid for `capacity` writes, and
+// `out_num_violations` must be valid for writes.
+enum GomoriStatus gomori_validate_turn(const struct GomoriBoard *board,
+                                       const struct GomoriCard *hand,
+                                       const struct GomoriCardToPlay *cards,
+                                       size_t num_cards,
+                                       struct GomoriTurnViolation *out_violations,
+                                       size_t capacity,
+                                       size_t *out_num_violations);
+
+// Plays a turn with the given hand of five cards, modifying the board in place.
+//
+// Returns `GOMORI_STATUS_ILLEGAL_MOVE` and leaves the board unchanged if the turn is illegal.
+// The hand is only used for checking the turn, and no cards are drawn. `out_cards_won`
+// receives the cards won by all cards of the turn, and may be null if they are not needed.
+//
+// # Safety
+//
+// `board` must be a valid board, `hand` must point to 5 cards, `cards` must point to
+// `num_cards` cards, and `out_cards_won` must be null or valid for writes.
+enum GomoriStatus gomori_board_play_turn(struct GomoriBoard *board,
+                                         const struct GomoriCard *hand,
+                                         const struct GomoriCardToPlay *cards,
+                                         size_t num_cards,
+                                         GomoriCardsSet *out_cards_won);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GOMORI_H */
@@ -0,0 +1,571 @@
+//! A C API for the gomori rules engine, so that bots written in other languages can use the
+//! reference implementation of the rules instead of re-implementing them.
+//!
+//! The header `include/gomori.h` is generated from this file by the build script. After
+//! changing the API, update it with `UPDATE_HEADER=1 cargo test -p gomori-ffi`.
+//!
+//! # Conventions
+//!
+//! - Boards are opaque handles created by [`gomori_board_new()`] or [`gomori_board_clone()`],
+//!   which must be freed with [`gomori_board_free()`].
+//! - Functions that can fail return a [`GomoriStatus`] and write their results to out-pointers.
+//!   Out-pointers are only written to if the function returns [`GomoriStatus::Ok`].
+//! - Functions that return a list take a buffer and its capacity. They write at most `capacity`
+//!   elements, and always report the full length of the list, so the caller can retry with a
+//!   larger buffer if needed.
+//! - Sets of cards are passed as a bitmask ([`GomoriCardsSet`]).
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::slice;
+
+use gomori::{
+    validate_turn, Board, Card, CardToPlay, CardsSet, Field, IllegalMove, PlayTurnResponse,
+    PlayerState, Rank, Suit,
+};
+
+const SUITS: [Suit; 4] = [Suit::Diamond, Suit::Heart, Suit::Spade, Suit::Club];
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+/// The result of a function call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GomoriStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer,
+    /// A card has an invalid suit or rank, or a set of cards has bits above bit 51.
+    InvalidCard,
+    /// The fields do not form a valid board, e.g. because they don't fit into a 4 x 4 area.
+    InvalidBoard,
+    /// The card cannot be played at that position.
+    IllegalCardPlayed,
+    /// The turn is illegal, use `gomori_validate_turn()` to find out why.
+    IllegalMove,
+    /// An internal error occurred. This is a bug in the library.
+    Panic,
+}
+
+/// A card, with the suit `0` to `3` meaning ♦, ♥, ♠ and ♣, and the rank `0` to `12` meaning
+/// 2, 3, ..., 10, J, Q, K, A.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GomoriCard {
+    pub suit: u8,
+    pub rank: u8,
+}
+
+/// A set of cards, where the card with suit `s` and rank `r` is bit `4 * r + s`.
+pub type GomoriCardsSet = u64;
+
+/// A field on the board.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GomoriField {
+    pub i: i8,
+    pub j: i8,
+    /// Whether there is a face-up card on top of the field.
+    pub has_top_card: bool,
+    /// The face-up card, only meaningful if `has_top_card` is set.
+    pub top_card: GomoriCard,
+    /// The cards below the top card, or all cards if there is no face-up card.
+    pub hidden_cards: GomoriCardsSet,
+}
+
+/// A card to be placed on the board.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GomoriCardToPlay {
+    pub card: GomoriCard,
+    pub i: i8,
+    pub j: i8,
+    /// Whether there is a target for the ability of a king.
+    pub has_target: bool,
+    /// The target for the ability of a king, only meaningful if `has_target` is set.
+    pub target_i: i8,
+    pub target_j: i8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GomoriCoordinates {
+    pub i: i8,
+    pub j: i8,
+}
+
+/// The effects of placing a single card.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GomoriEffects {
+    /// The cards that were won by placing the card.
+    pub cards_won: GomoriCardsSet,
+    /// Whether another card must be played.
+    pub combo: bool,
+}
+
+/// The kind of a rule violation in a turn.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GomoriViolationKind {
+    PlayedCardNotInHand,
+    PlayedZeroCards,
+    PlayedMoreThanFiveCards,
+    IllegalCardPlayed,
+    PlayedCardAfterEndOfCombo,
+    PrematurelyEndedCombo,
+}
+
+/// A rule violation in a turn, see `gomori_validate_turn()`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct GomoriTurnViolation {
+    pub kind: GomoriViolationKind,
+    /// The index of the offending card in the turn, or -1 if the violation is about the whole turn.
+    pub card_idx: i32,
+}
+
+/// A board with at least one card on it.
+pub struct GomoriBoard(Board);
+
+// Catches panics, since unwinding into C is undefined behavior.
+fn guard(f: impl FnOnce() -> Result<(), GomoriStatus>) -> GomoriStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => GomoriStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => GomoriStatus::Panic,
+    }
+}
+
+fn non_null<'a, T>(ptr: *const T) -> Result<&'a T, GomoriStatus> {
+    // SAFETY: The caller guarantees that non-null pointers are valid.
+    unsafe { ptr.as_ref() }.ok_or(GomoriStatus::NullPointer)
+}
+
+fn non_null_mut<'a, T>(ptr: *mut T) -> Result<&'a mut T, GomoriStatus> {
+    // SAFETY: The caller guarantees that non-null pointers are valid.
+    unsafe { ptr.as_mut() }.ok_or(GomoriStatus::NullPointer)
+}
+
+// A null pointer is fine for an empty slice.
+fn slice_from_raw<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], GomoriStatus> {
+    if len == 0 {
+        Ok(&[])
+    } else if ptr.is_null() {
+        Err(GomoriStatus::NullPointer)
+    } else {
+        // SAFETY: The caller guarantees that the pointer is valid for len elements.
+        Ok(unsafe { slice::from_raw_parts(ptr, len) })
+    }
+}
+
+// Writes as many elements as fit into the buffer, and returns the full length.
+fn write_list<T: Copy>(
+    items: &[T],
+    out: *mut T,
+    capacity: usize,
+    out_len: *mut usize,
+) -> Result<(), GomoriStatus> {
+    let out_len = non_null_mut(out_len)?;
+    let num_written = items.len().min(capacity);
+    if num_written > 0 {
+        if out.is_null() {
+            return Err(GomoriStatus::NullPointer);
+        }
+        // SAFETY: The caller guarantees that the buffer is valid for capacity elements.
+        unsafe { slice::from_raw_parts_mut(out, num_written) }
+            .copy_from_slice(&items[..num_written]);
+    }
+    *out_len = items.len();
+    Ok(())
+}
+
+impl TryFrom<GomoriCard> for Card {
+    type Error = GomoriStatus;
+
+    fn try_from(card: GomoriCard) -> Result<Self, Self::Error> {
+        match (
+            SUITS.get(usize::from(card.suit)),
+            RANKS.get(usize::from(card.rank)),
+        ) {
+            (Some(&suit), Some(&rank)) => Ok(Card { suit, rank }),
+            _ => Err(GomoriStatus::InvalidCard),
+        }
+    }
+}
+
+impl From<Card> for GomoriCard {
+    fn from(card: Card) -> Self {
+        GomoriCard {
+            suit: card.suit as u8,
+            rank: card.rank as u8,
+        }
+    }
+}
+
+fn card_bit(card: Card) -> u32 {
+    4 * card.rank as u32 + card.suit as u32
+}
+
+fn cards_set_to_bits(cards: CardsSet) -> GomoriCardsSet {
+    cards
+        .into_iter()
+        .fold(0, |bits, card| bits | 1 << card_bit(card))
+}
+
+fn cards_set_from_bits(bits: GomoriCardsSet) -> Result<CardsSet, GomoriStatus> {
+    if bits >> 52 != 0 {
+        return Err(GomoriStatus::InvalidCard);
+    }
+    let mut cards = CardsSet::new();
+    for &suit in &SUITS {
+        for &rank in &RANKS {
+            let card = Card { suit, rank };
+            if bits & 1 << card_bit(card) != 0 {
+                cards = cards.insert(card);
+            }
+        }
+    }
+    Ok(cards)
+}
+
+fn card_to_play_from_ffi(ctp: &GomoriCardToPlay) -> Result<CardToPlay, GomoriStatus> {
+    Ok(CardToPlay {
+        card: Card::try_from(ctp.card)?,
+        i: ctp.i,
+        j: ctp.j,
+        target_field_for_king_ability: ctp.has_target.then_some((ctp.target_i, ctp.target_j)),
+    })
+}
+
+fn turn_from_ffi(cards: &[GomoriCardToPlay]) -> Result<PlayTurnResponse, GomoriStatus> {
+    Ok(PlayTurnResponse(
+        cards
+            .iter()
+            .map(card_to_play_from_ffi)
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+// The bot doesn't know the draw pile, which is irrelevant for the legality of a turn.
+fn player_state_from_hand(hand: *const GomoriCard) -> Result<PlayerState, GomoriStatus> {
+    let hand = slice_from_raw(hand, 5)?;
+    let mut cards = [Card {
+        suit: Suit::Diamond,
+        rank: Rank::Two,
+    }; 5];
+    for (card, &ffi_card) in cards.iter_mut().zip(hand) {
+        *card = Card::try_from(ffi_card)?;
+    }
+    Ok(PlayerState {
+        draw_pile: Vec::new(),
+        hand: cards,
+        cards_won: CardsSet::new(),
+    })
+}
+
+/// Creates a board from a list of fields.
+///
+/// On success, `*out_board` is set to a new board, which must be freed with `gomori_board_free()`.
+///
+/// # Safety
+///
+/// `fields` must point to `num_fields` fields, and `out_board` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_new(
+    fields: *const GomoriField,
+    num_fields: usize,
+    out_board: *mut *mut GomoriBoard,
+) -> GomoriStatus {
+    guard(|| {
+        let out_board = non_null_mut(out_board)?;
+        let fields = slice_from_raw(fields, num_fields)?
+            .iter()
+            .map(|field| {
+                Ok(Field {
+                    i: field.i,
+                    j: field.j,
+                    top_card: match field.has_top_card {
+                        true => Some(Card::try_from(field.top_card)?),
+                        false => None,
+                    },
+                    hidden_cards: cards_set_from_bits(field.hidden_cards)?
+                        .into_iter()
+                        .collect(),
+                })
+            })
+            .collect::<Result<Vec<_>, GomoriStatus>>()?;
+        let board = Board::try_new(&fields).map_err(|_| GomoriStatus::InvalidBoard)?;
+        *out_board = Box::into_raw(Box::new(GomoriBoard(board)));
+        Ok(())
+    })
+}
+
+/// Creates a copy of the board, which must be freed with `gomori_board_free()`.
+///
+/// Returns null if `board` is null.
+///
+/// # Safety
+///
+/// `board` must be null or a valid board.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_clone(board: *const GomoriBoard) -> *mut GomoriBoard {
+    match non_null(board) {
+        Ok(GomoriBoard(board)) => Box::into_raw(Box::new(GomoriBoard(board.clone()))),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Frees a board. Does nothing if `board` is null.
+///
+/// # Safety
+///
+/// `board` must be null or a valid board, which must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_free(board: *mut GomoriBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+/// Writes the fields of the board, sorted by their coordinates, to `out_fields`.
+///
+/// # Safety
+///
+/// `board` must be a valid board, `out_fields` must be valid for `capacity` writes, and
+/// `out_num_fields` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_fields(
+    board: *const GomoriBoard,
+    out_fields: *mut GomoriField,
+    capacity: usize,
+    out_num_fields: *mut usize,
+) -> GomoriStatus {
+    guard(|| {
+        let GomoriBoard(board) = non_null(board)?;
+        let fields: Vec<GomoriField> = board
+            .to_fields_vec()
+            .into_iter()
+            .map(|field| GomoriField {
+                i: field.i,
+                j: field.j,
+                has_top_card: field.top_card.is_some(),
+                top_card: field
+                    .top_card
+                    .map(GomoriCard::from)
+                    .unwrap_or(GomoriCard { suit: 0, rank: 0 }),
+                hidden_cards: cards_set_to_bits(CardsSet::from_iter(field.hidden_cards)),
+            })
+            .collect();
+        write_list(&fields, out_fields, capacity, out_num_fields)
+    })
+}
+
+/// Writes the coordinates where the card can be placed to `out_coordinates`.
+///
+/// There are at most 49 such coordinates. Kings that are placed on another card need a target
+/// for their ability, see `Board::locations_for_card` in the Rust documentation.
+///
+/// # Safety
+///
+/// `board` must be a valid board, `out_coordinates` must be valid for `capacity` writes, and
+/// `out_num_coordinates` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_locations_for_card(
+    board: *const GomoriBoard,
+    card: GomoriCard,
+    out_coordinates: *mut GomoriCoordinates,
+    capacity: usize,
+    out_num_coordinates: *mut usize,
+) -> GomoriStatus {
+    guard(|| {
+        let GomoriBoard(board) = non_null(board)?;
+        let coordinates: Vec<GomoriCoordinates> = board
+            .locations_for_card(Card::try_from(card)?)
+            .into_iter()
+            .map(|(i, j)| GomoriCoordinates { i, j })
+            .collect();
+        write_list(&coordinates, out_coordinates, capacity, out_num_coordinates)
+    })
+}
+
+/// Calculates the effects of placing a card, without modifying the board.
+///
+/// Returns `GOMORI_STATUS_ILLEGAL_CARD_PLAYED` if the card cannot be placed there.
+///
+/// # Safety
+///
+/// `board` and `card_to_play` must be valid, and `out_effects` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_calculate(
+    board: *const GomoriBoard,
+    card_to_play: *const GomoriCardToPlay,
+    out_effects: *mut GomoriEffects,
+) -> GomoriStatus {
+    guard(|| {
+        let GomoriBoard(board) = non_null(board)?;
+        let card_to_play = card_to_play_from_ffi(non_null(card_to_play)?)?;
+        let out_effects = non_null_mut(out_effects)?;
+        let effects = board
+            .calculate(card_to_play)
+            .map_err(|_| GomoriStatus::IllegalCardPlayed)?;
+        *out_effects = GomoriEffects {
+            cards_won: cards_set_to_bits(effects.cards_won),
+            combo: effects.combo,
+        };
+        Ok(())
+    })
+}
+
+/// Places a card on the board, modifying it in place.
+///
+/// Returns `GOMORI_STATUS_ILLEGAL_CARD_PLAYED` and leaves the board unchanged if the card
+/// cannot be placed there. `out_effects` may be null if the effects are not needed.
+///
+/// # Safety
+///
+/// `board` and `card_to_play` must be valid, and `out_effects` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_play_card(
+    board: *mut GomoriBoard,
+    card_to_play: *const GomoriCardToPlay,
+    out_effects: *mut GomoriEffects,
+) -> GomoriStatus {
+    guard(|| {
+        let GomoriBoard(board) = non_null_mut(board)?;
+        let card_to_play = card_to_play_from_ffi(non_null(card_to_play)?)?;
+        let undo = board
+            .make(&card_to_play)
+            .map_err(|_| GomoriStatus::IllegalCardPlayed)?;
+        if let Some(out_effects) = out_effects.as_mut() {
+            *out_effects = GomoriEffects {
+                cards_won: cards_set_to_bits(undo.cards_won),
+                combo: undo.combo,
+            };
+        }
+        Ok(())
+    })
+}
+
+/// Finds all rule violations in a turn with the given hand of five cards, and writes them
+/// to `out_violations`. A legal turn has no violations.
+///
+/// # Safety
+///
+/// `board` must be a valid board, `hand` must point to 5 cards, `cards` must point to
+/// `num_cards` cards, `out_violations` must be valid for `capacity` writes, and
+/// `out_num_violations` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_validate_turn(
+    board: *const GomoriBoard,
+    hand: *const GomoriCard,
+    cards: *const GomoriCardToPlay,
+    num_cards: usize,
+    out_violations: *mut GomoriTurnViolation,
+    capacity: usize,
+    out_num_violations: *mut usize,
+) -> GomoriStatus {
+    guard(|| {
+        let GomoriBoard(board) = non_null(board)?;
+        let state = player_state_from_hand(hand)?;
+        let turn = turn_from_ffi(slice_from_raw(cards, num_cards)?)?;
+        let violations: Vec<GomoriTurnViolation> = validate_turn(&state, board, &turn)
+            .into_iter()
+            .map(|violation| GomoriTurnViolation {
+                kind: match violation.err {
+                    IllegalMove::PlayedCardNotInHand => GomoriViolationKind::PlayedCardNotInHand,
+                    IllegalMove::PlayedZeroCards => GomoriViolationKind::PlayedZeroCards,
+                    IllegalMove::PlayedMoreThanFiveCards => {
+                        GomoriViolationKind::PlayedMoreThanFiveCards
+                    }
+                    IllegalMove::IllegalCardPlayed { .. } => GomoriViolationKind::IllegalCardPlayed,
+                    IllegalMove::PlayedCardAfterEndOfCombo { .. } => {
+                        GomoriViolationKind::PlayedCardAfterEndOfCombo
+                    }
+                    IllegalMove::PrematurelyEndedCombo { .. } => {
+                        GomoriViolationKind::PrematurelyEndedCombo
+                    }
+                },
+                card_idx: violation.card_idx.map_or(-1, |idx| idx as i32),
+            })
+            .collect();
+        write_list(&violations, out_violations, capacity, out_num_violations)
+    })
+}
+
+/// Plays a turn with the given hand of five cards, modifying the board in place.
+///
+/// Returns `GOMORI_STATUS_ILLEGAL_MOVE` and leaves the board unchanged if the turn is illegal.
+/// The hand is only used for checking the turn, and no cards are drawn. `out_cards_won`
+/// receives the cards won by all cards of the turn, and may be null if they are not needed.
+///
+/// # Safety
+///
+/// `board` must be a valid board, `hand` must point to 5 cards, `cards` must point to
+/// `num_cards` cards, and `out_cards_won` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gomori_board_play_turn(
+    board: *mut GomoriBoard,
+    hand: *const GomoriCard,
+    cards: *const GomoriCardToPlay,
+    num_cards: usize,
+    out_cards_won: *mut GomoriCardsSet,
+) -> GomoriStatus {
+    guard(|| {
+        let GomoriBoard(board) = non_null_mut(board)?;
+        let state = player_state_from_hand(hand)?;
+        let turn = turn_from_ffi(slice_from_raw(cards, num_cards)?)?;
+        if !validate_turn(&state, board, &turn).is_empty() {
+            return Err(GomoriStatus::IllegalMove);
+        }
+        let mut new_board = board.clone();
+        let mut cards_won = CardsSet::new();
+        for card_to_play in turn.0 {
+            let effects = new_board
+                .calculate(card_to_play)
+                .map_err(|_| GomoriStatus::IllegalMove)?;
+            cards_won |= effects.cards_won;
+            new_board = effects.execute();
+        }
+        *board = new_board;
+        if let Some(out_cards_won) = out_cards_won.as_mut() {
+            *out_cards_won = cards_set_to_bits(cards_won);
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cards_set_bits_roundtrip() {
+        let cards = CardsSet::from_iter([
+            Card {
+                suit: Suit::Club,
+                rank: Rank::Ace,
+            },
+            Card {
+                suit: Suit::Diamond,
+                rank: Rank::Two,
+            },
+        ]);
+        let bits = cards_set_to_bits(cards);
+        assert_eq!(bits, 1 | 1 << 51);
+        assert_eq!(cards_set_from_bits(bits).unwrap(), cards);
+        assert_eq!(cards_set_from_bits(1 << 52), Err(GomoriStatus::InvalidCard));
+    }
+}
@@ -0,0 +1,136 @@
+// Exercises the C API, see tests/c_api.rs for how this is built and run.
+
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "gomori.h"
+
+// Unlike assert(), this also evaluates the condition when compiled with NDEBUG
+#define CHECK(cond)                                                            \
+  do {                                                                         \
+    if (!(cond)) {                                                             \
+      fprintf(stderr, "%s:%d: Check failed: %s\n", __FILE__, __LINE__, #cond); \
+      exit(1);                                                                 \
+    }                                                                          \
+  } while (0)
+
+enum { DIAMOND = 0, HEART = 1, SPADE = 2, CLUB = 3 };
+enum { TWO = 0, THREE = 1, FOUR = 2, FIVE = 3, SIX = 4, SEVEN = 5, EIGHT = 6, NINE = 7, TEN = 8, ACE = 12 };
+
+static GomoriCard card(uint8_t suit, uint8_t rank) {
+  GomoriCard c = {suit, rank};
+  return c;
+}
+
+static GomoriCardsSet bit(GomoriCard c) { return (GomoriCardsSet)1 << (4 * c.rank + c.suit); }
+
+static GomoriField field(int8_t i, int8_t j, GomoriCard top_card) {
+  GomoriField f = {i, j, true, top_card, 0};
+  return f;
+}
+
+static GomoriCardToPlay card_to_play(GomoriCard c, int8_t i, int8_t j) {
+  GomoriCardToPlay ctp = {c, i, j, false, 0, 0};
+  return ctp;
+}
+
+static size_t num_fields(const GomoriBoard *board) {
+  GomoriField fields[16];
+  size_t n = 0;
+  CHECK(gomori_board_fields(board, fields, 16, &n) == GOMORI_STATUS_OK);
+  return n;
+}
+
+int main(void) {
+  // Three diamonds in a row
+  GomoriField fields[] = {
+      field(0, 0, card(DIAMOND, SEVEN)),
+      field(0, 1, card(DIAMOND, EIGHT)),
+      field(0, 2, card(DIAMOND, NINE)),
+  };
+  GomoriBoard *board = NULL;
+  CHECK(gomori_board_new(fields, 3, &board) == GOMORI_STATUS_OK);
+  CHECK(num_fields(board) == 3);
+
+  // Invalid input is rejected
+  GomoriBoard *invalid = NULL;
+  GomoriField too_large[] = {field(0, 0, card(HEART, TWO)), field(0, 4, card(HEART, THREE))};
+  CHECK(gomori_board_new(too_large, 2, &invalid) == GOMORI_STATUS_INVALID_BOARD);
+  GomoriField invalid_card[] = {field(0, 0, card(4, TWO))};
+  CHECK(gomori_board_new(invalid_card, 1, &invalid) == GOMORI_STATUS_INVALID_CARD);
+  CHECK(gomori_board_new(fields, 3, NULL) == GOMORI_STATUS_NULL_POINTER);
+  CHECK(invalid == NULL);
+
+  // Locations, with a buffer that is too small
+  GomoriCoordinates coordinates[49];
+  size_t num_coordinates = 0;
+  CHECK(gomori_board_locations_for_card(board, card(CLUB, TWO), coordinates, 2, &num_coordinates) ==
+         GOMORI_STATUS_OK);
+  CHECK(num_coordinates > 2 && num_coordinates <= 49);
+
+  // Calculating doesn't modify the board, playing does
+  GomoriCardToPlay ten = card_to_play(card(DIAMOND, TEN), 0, 3);
+  GomoriEffects effects;
+  CHECK(gomori_board_calculate(board, &ten, &effects) == GOMORI_STATUS_OK);
+  CHECK(num_fields(board) == 3);
+  GomoriBoard *copy = gomori_board_clone(board);
+  CHECK(gomori_board_play_card(copy, &ten, &effects) == GOMORI_STATUS_OK);
+  CHECK(effects.cards_won ==
+         (bit(card(DIAMOND, SEVEN)) | bit(card(DIAMOND, EIGHT)) | bit(card(DIAMOND, NINE))));
+  CHECK(!effects.combo);
+  CHECK(num_fields(copy) == 1);
+  GomoriCardToPlay out_of_bounds = card_to_play(card(DIAMOND, TEN), 0, 4);
+  CHECK(gomori_board_play_card(board, &out_of_bounds, NULL) == GOMORI_STATUS_ILLEGAL_CARD_PLAYED);
+  gomori_board_free(copy);
+
+  // Turn validation
+  GomoriCard hand[5] = {card(CLUB, TWO), card(CLUB, THREE), card(CLUB, FOUR), card(CLUB, FIVE),
+                        card(DIAMOND, TEN)};
+  GomoriCardToPlay illegal_turn[] = {card_to_play(card(HEART, ACE), 5, 5)};
+  GomoriTurnViolation violations[8];
+  size_t num_violations = 0;
+  CHECK(gomori_validate_turn(board, hand, illegal_turn, 1, violations, 8, &num_violations) ==
+         GOMORI_STATUS_OK);
+  CHECK(num_violations == 2);
+  CHECK(violations[0].kind == GOMORI_VIOLATION_KIND_PLAYED_CARD_NOT_IN_HAND);
+  CHECK(violations[0].card_idx == 0);
+  CHECK(violations[1].kind == GOMORI_VIOLATION_KIND_ILLEGAL_CARD_PLAYED);
+  CHECK(gomori_validate_turn(board, hand, NULL, 0, violations, 8, &num_violations) ==
+         GOMORI_STATUS_OK);
+  CHECK(num_violations == 1);
+  CHECK(violations[0].kind == GOMORI_VIOLATION_KIND_PLAYED_ZERO_CARDS);
+  CHECK(violations[0].card_idx == -1);
+
+  // Playing turns
+  GomoriCardsSet cards_won = 0;
+  CHECK(gomori_board_play_turn(board, hand, illegal_turn, 1, &cards_won) == GOMORI_STATUS_ILLEGAL_MOVE);
+  CHECK(num_fields(board) == 3);
+  GomoriCardToPlay legal_turn[] = {ten};
+  CHECK(gomori_board_play_turn(board, hand, legal_turn, 1, &cards_won) == GOMORI_STATUS_OK);
+  CHECK(cards_won == effects.cards_won);
+  CHECK(num_fields(board) == 1);
+  gomori_board_free(board);
+
+  // The last turn of a game, where all other cards are on the board
+  GomoriField final_fields[] = {
+      field(0, 0, card(DIAMOND, SEVEN)),
+      field(0, 1, card(DIAMOND, EIGHT)),
+      field(0, 2, card(DIAMOND, NINE)),
+  };
+  GomoriCardsSet hand_cards = 0;
+  for (size_t idx = 0; idx < 5; idx++) {
+    hand_cards |= bit(hand[idx]);
+  }
+  GomoriCardsSet other_cards = (((GomoriCardsSet)1 << 52) - 1) & ~hand_cards;
+  final_fields[0].hidden_cards = other_cards & ~bit(final_fields[0].top_card) &
+                                 ~bit(final_fields[1].top_card) & ~bit(final_fields[2].top_card);
+  CHECK(gomori_board_new(final_fields, 3, &board) == GOMORI_STATUS_OK);
+  CHECK(gomori_board_play_turn(board, hand, legal_turn, 1, &cards_won) == GOMORI_STATUS_OK);
+  CHECK(cards_won == other_cards);
+  CHECK(num_fields(board) == 1);
+
+  gomori_board_free(board);
+  gomori_board_free(NULL);
+  printf("All C API tests passed\n");
+  return 0;
+}
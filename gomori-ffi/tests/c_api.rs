@@ -0,0 +1,59 @@
+// Compiles tests/c/test_gomori.c against the static library and runs it.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+// The header generated by the build script must match the checked-in one.
+#[test]
+fn header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/gomori.h"));
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include/gomori.h");
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        std::fs::write(&path, generated).unwrap();
+    }
+    let checked_in = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        checked_in == generated,
+        "{} is out of date, run the tests with UPDATE_HEADER=1 to update it",
+        path.display()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn c_test_program() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("gomori-ffi");
+
+    // The library is built separately, because the library built for this test may have
+    // the python feature of the gomori crate enabled, if the whole workspace is being tested.
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--lib", "--manifest-path"])
+        .arg(crate_dir.join("Cargo.toml"))
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "Building the library failed");
+
+    let executable = target_dir.join("test_gomori");
+    let compiler = std::env::var("CC").unwrap_or_else(|_| String::from("cc"));
+    let status = Command::new(&compiler)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(&executable)
+        .arg(crate_dir.join("tests/c/test_gomori.c"))
+        .arg("-I")
+        .arg(crate_dir.join("include"))
+        .arg(target_dir.join("debug/libgomori_ffi.a"))
+        .args(["-lpthread", "-ldl", "-lm"])
+        .status()
+        .unwrap_or_else(|err| panic!("Could not run the C compiler {}: {}", compiler, err));
+    assert!(status.success(), "Compiling the C test program failed");
+
+    let output = Command::new(&executable).output().unwrap();
+    assert!(
+        output.status.success(),
+        "The C test program failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -1,9 +1,21 @@
 from gomori._gomori import *
-from typing import List
+from typing import List, Optional
 
 import json
 
 class Bot:
+	def name(self) -> str:
+		"""The name that is reported to the judge. Defaults to the class name."""
+		return type(self).__name__
+
+	def version(self) -> str:
+		"""The version that is reported to the judge."""
+		return "unknown"
+
+	def use_binary_encoding(self) -> bool:
+		"""Whether to accept the binary encoding of the protocol, which is faster than JSON."""
+		return False
+
 	def new_game(self, color: Color):
 		raise NotImplementedError()
 
@@ -17,3 +29,20 @@ class Bot:
 		cards_won_by_opponent: CardsSet
 	) -> PlayTurnResponse:
 		raise NotImplementedError()
+
+	def opponent_turn(self, cards_to_play: PlayTurnResponse, first_turn: bool):
+		"""Called with the opponent's previous turn, right before play_turn(). A skipped turn has
+		no cards, and the first turn of the game is a single card at (0, 0)."""
+		pass
+
+	def game_over(
+		self,
+		winner: Optional[Color],
+		cards_won: CardsSet,
+		cards_won_by_opponent: CardsSet,
+		board: Optional[Board],
+		reason: GameOverReason
+	):
+		"""Called after every game. The winner is None for a tie, and the board is None if
+		the first turn was not played successfully."""
+		pass
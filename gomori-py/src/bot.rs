@@ -1,6 +1,9 @@
-use gomori::{Board, Card, CardsSet, Color, Field, PlayTurnResponse};
+use gomori::{
+    Board, Card, CardsSet, Color, GameOverReason, InvalidBoard, OpponentTurn, PlayTurnResponse,
+};
 use gomori_bot_utils::Bot;
-use pyo3::{pyfunction, types::PyDict, Py, PyObject, Python};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::{pyfunction, types::PyDict, Py, PyErr, PyObject, PyResult, Python};
 
 struct PythonBot {
     bot: PyObject,
@@ -8,6 +11,36 @@ struct PythonBot {
 
 // TODO: Re-evaluate this whole design
 impl gomori_bot_utils::Bot for PythonBot {
+    fn name(&self) -> String {
+        Python::with_gil(|py| {
+            self.bot
+                .call_method0(py, "name")
+                .expect("Call to name() failed")
+                .extract(py)
+                .expect("name() returned wrong type")
+        })
+    }
+
+    fn version(&self) -> String {
+        Python::with_gil(|py| {
+            self.bot
+                .call_method0(py, "version")
+                .expect("Call to version() failed")
+                .extract(py)
+                .expect("version() returned wrong type")
+        })
+    }
+
+    fn use_binary_encoding(&self) -> bool {
+        Python::with_gil(|py| {
+            self.bot
+                .call_method0(py, "use_binary_encoding")
+                .expect("Call to use_binary_encoding() failed")
+                .extract(py)
+                .expect("use_binary_encoding() returned wrong type")
+        })
+    }
+
     fn new_game(&mut self, color: Color) {
         Python::with_gil(|py| {
             let kwargs = PyDict::new(py);
@@ -37,7 +70,7 @@ impl gomori_bot_utils::Bot for PythonBot {
     fn play_turn(
         &mut self,
         cards: [Card; 5],
-        fields: Vec<Field>,
+        board: Board,
         cards_won_by_opponent: CardsSet,
     ) -> PlayTurnResponse {
         Python::with_gil(|py| {
@@ -46,7 +79,7 @@ impl gomori_bot_utils::Bot for PythonBot {
                 .set_item("cards", cards.map(|card| Py::new(py, card).unwrap()))
                 .unwrap();
             kwargs
-                .set_item("board", Py::new(py, Board::new(&fields)).unwrap())
+                .set_item("board", Py::new(py, board).unwrap())
                 .unwrap();
             kwargs
                 .set_item(
@@ -61,9 +94,66 @@ impl gomori_bot_utils::Bot for PythonBot {
                 .expect("play_turn() returned wrong type")
         })
     }
+
+    fn opponent_turn(&mut self, opponent_turn: OpponentTurn) {
+        Python::with_gil(|py| {
+            let kwargs = PyDict::new(py);
+            let cards_to_play = PlayTurnResponse(opponent_turn.cards_to_play());
+            kwargs
+                .set_item("cards_to_play", Py::new(py, cards_to_play).unwrap())
+                .unwrap();
+            kwargs
+                .set_item(
+                    "first_turn",
+                    matches!(opponent_turn, OpponentTurn::FirstTurn { .. }),
+                )
+                .unwrap();
+            self.bot
+                .call_method(py, "opponent_turn", (), Some(kwargs))
+                .expect("Call to opponent_turn() failed");
+        })
+    }
+
+    fn game_over(
+        &mut self,
+        winner: Option<Color>,
+        cards_won: CardsSet,
+        cards_won_by_opponent: CardsSet,
+        board: Option<Board>,
+        reason: GameOverReason,
+    ) {
+        Python::with_gil(|py| {
+            let kwargs = PyDict::new(py);
+            kwargs
+                .set_item("winner", winner.map(|color| Py::new(py, color).unwrap()))
+                .unwrap();
+            kwargs
+                .set_item("cards_won", Py::new(py, cards_won).unwrap())
+                .unwrap();
+            kwargs
+                .set_item(
+                    "cards_won_by_opponent",
+                    Py::new(py, cards_won_by_opponent).unwrap(),
+                )
+                .unwrap();
+            let board = board.map(|board| Py::new(py, board).unwrap());
+            kwargs.set_item("board", board).unwrap();
+            kwargs
+                .set_item("reason", Py::new(py, reason).unwrap())
+                .unwrap();
+            self.bot
+                .call_method(py, "game_over", (), Some(kwargs))
+                .expect("Call to game_over() failed");
+        })
+    }
 }
 
 #[pyfunction]
-pub fn run_bot(bot: PyObject) {
-    PythonBot { bot }.run().unwrap()
+pub fn run_bot(bot: PyObject) -> PyResult<()> {
+    PythonBot { bot }
+        .run()
+        .map_err(|err| match err.downcast::<InvalidBoard>() {
+            Ok(invalid_board) => PyErr::from(invalid_board),
+            Err(err) => PyRuntimeError::new_err(format!("{:#}", err)),
+        })
 }
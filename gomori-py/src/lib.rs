@@ -14,6 +14,10 @@ fn gomori(py: Python, m: &PyModule) -> PyResult<()> {
         "IllegalMove",
         py.get_type::<::gomori::IllegalMoveException>(),
     )?;
+    m.add(
+        "InvalidBoard",
+        py.get_type::<::gomori::InvalidBoardException>(),
+    )?;
     m.add_class::<::gomori::BitBoard>()?;
     m.add_class::<::gomori::Board>()?;
     m.add_class::<::gomori::BoundingBox>()?;
@@ -23,10 +27,12 @@ fn gomori(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<::gomori::Color>()?;
     m.add_class::<::gomori::CompactField>()?;
     m.add_class::<::gomori::Field>()?;
+    m.add_class::<::gomori::GameOverReason>()?;
     m.add_class::<::gomori::PlayTurnResponse>()?;
     m.add_class::<::gomori::PyCalculatedEffects>()?;
     m.add_class::<::gomori::Rank>()?;
     m.add_class::<::gomori::Suit>()?;
+    m.add_class::<::gomori::Undo>()?;
     m.add_function(wrap_pyfunction!(bot::run_bot, m)?)?;
     Ok(())
 }
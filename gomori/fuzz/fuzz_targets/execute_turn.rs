@@ -0,0 +1,99 @@
+//! Plays arbitrary turns on boards that are reached by playing a random game.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use gomori::{
+    legal_turns, validate_turn, Board, Card, CardToPlay, CardsSet, Color, Game, PlayTurnResponse,
+    Rank, Suit,
+};
+use libfuzzer_sys::fuzz_target;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    seed: u64,
+    /// Selects one of the legal turns for every turn before the fuzzed one.
+    previous_turns: Vec<u8>,
+    turn: Vec<FuzzedCardToPlay>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzedCardToPlay {
+    /// A card from the hand, or any card.
+    card: u8,
+    /// The coordinates are relative to the board's bounding box, to make it easier for the
+    /// fuzzer to find legal placements.
+    i: i8,
+    j: i8,
+    target_field_for_king_ability: Option<(i8, i8)>,
+}
+
+const SUITS: [Suit; 4] = [Suit::Diamond, Suit::Heart, Suit::Spade, Suit::Club];
+const RANKS: [Rank; 13] = [
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+    Rank::Jack,
+    Rank::Queen,
+    Rank::King,
+    Rank::Ace,
+];
+
+fuzz_target!(|input: Input| {
+    let mut rng = StdRng::seed_from_u64(input.seed);
+    let mut game = Game::new([Color::Red, Color::Black], 0, &mut rng);
+    game.apply_first_turn(game.player_state(0).hand[0]).unwrap();
+    for choice in input.previous_turns {
+        if game.is_over() {
+            return;
+        }
+        let board = game.board().unwrap();
+        let hand = CardsSet::from_iter(game.player_state(game.current_player_idx()).hand);
+        // Limit the work, since there can be thousands of legal turns
+        let turns: Vec<Vec<CardToPlay>> = legal_turns(board, hand).take(256).collect();
+        let turn = turns[usize::from(choice) % turns.len()].clone();
+        game.apply_turn(PlayTurnResponse(turn)).unwrap();
+    }
+    if game.is_over() {
+        return;
+    }
+
+    let board = game.board().unwrap().clone();
+    let state = game.player_state(game.current_player_idx()).clone();
+    let bbox = board.bbox();
+    let turn = PlayTurnResponse(
+        input
+            .turn
+            .into_iter()
+            .map(|ctp| CardToPlay {
+                card: match ctp.card {
+                    idx @ 0..=127 => state.hand[usize::from(idx) % 5],
+                    idx => Card {
+                        suit: SUITS[usize::from(idx) % 4],
+                        rank: RANKS[usize::from(idx / 4) % 13],
+                    },
+                },
+                i: bbox.i_min.wrapping_add(ctp.i),
+                j: bbox.j_min.wrapping_add(ctp.j),
+                target_field_for_king_ability: ctp
+                    .target_field_for_king_ability
+                    .map(|(i, j)| (bbox.i_min.wrapping_add(i), bbox.j_min.wrapping_add(j))),
+            })
+            .collect(),
+    );
+
+    let violations = validate_turn(&state, &board, &turn);
+    let result = game.apply_turn(turn);
+    assert_eq!(violations.is_empty(), result.is_ok());
+    if result.is_ok() {
+        let board = game.board().unwrap();
+        assert_eq!(&Board::try_new(&board.to_fields_vec()).unwrap(), board);
+    }
+});
@@ -0,0 +1,59 @@
+//! Feeds arbitrary bytes into the parsing of requests (what a bot receives) and responses
+//! (what the judge receives) in both encodings, as well as the string notations.
+
+#![no_main]
+
+use std::str::FromStr;
+
+use gomori::{
+    BinaryMessage, Board, Card, CardToPlay, CardsSet, Okay, PlayTurnResponse, Position, Request,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(request) = serde_json::from_slice::<Request>(data) {
+        let serialized = serde_json::to_vec(&request).unwrap();
+        serde_json::from_slice::<Request>(&serialized).expect("A request roundtrips");
+
+        // This is what a bot does with the request
+        if let Request::PlayTurn { cards, fields, .. } = request {
+            if let Ok(board) = Board::try_new(&fields) {
+                let hand = CardsSet::from_iter(cards);
+                for card in hand {
+                    for card_to_play in board.placements_for_card(card) {
+                        board
+                            .calculate(card_to_play)
+                            .expect("A generated placement is legal");
+                    }
+                }
+                let _ = board.threats();
+            }
+        }
+    }
+
+    let _ = serde_json::from_slice::<Okay>(data);
+    let _ = serde_json::from_slice::<Card>(data);
+    if let Ok(response) = serde_json::from_slice::<PlayTurnResponse>(data) {
+        let serialized = serde_json::to_vec(&response).unwrap();
+        serde_json::from_slice::<PlayTurnResponse>(&serialized).expect("A response roundtrips");
+    }
+
+    if let Ok(request) = Request::decode(data) {
+        let mut encoded = Vec::new();
+        request.encode(&mut encoded);
+        Request::decode(&encoded).expect("A binary request roundtrips");
+    }
+    let _ = Okay::decode(data);
+    let _ = Card::decode(data);
+    let _ = PlayTurnResponse::decode(data);
+
+    if let Ok(s) = std::str::from_utf8(data) {
+        if let Ok(position) = Position::from_str(s) {
+            let reparsed = Position::from_str(&position.to_string()).unwrap();
+            assert_eq!(position, reparsed);
+        }
+        let _ = PlayTurnResponse::from_str(s);
+        let _ = CardToPlay::from_str(s);
+        let _ = Card::from_str(s);
+    }
+});
@@ -1,6 +1,8 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::{Card, CardToPlay, Field, Rank, Suit};
+use quickcheck::Arbitrary;
+
+use crate::{Board, Card, CardToPlay, CardsSet, Field, Rank, RuleSet, Suit};
 
 #[derive(Clone, Debug)]
 pub struct PlayCardInput {
@@ -27,31 +29,7 @@ impl quickcheck::Arbitrary for PlayCardInput {
         };
         already_played_cards.insert(other_card);
 
-        let mut cards_on_field = BTreeMap::new();
-        for played_card in already_played_cards {
-            let i = (u8::arbitrary(g) % 4) as i8 - 2;
-            let j = (u8::arbitrary(g) % 4) as i8 - 2;
-            cards_on_field
-                .entry((i, j))
-                .or_insert(BTreeSet::new())
-                .insert(played_card);
-        }
-
-        let mut fields = Vec::with_capacity(cards_on_field.len());
-        for ((i, j), mut cards) in cards_on_field {
-            let top_card = if bool::arbitrary(g) {
-                cards.pop_last()
-            } else {
-                None
-            };
-            fields.push(Field {
-                i,
-                j,
-                top_card,
-                hidden_cards: cards,
-            });
-        }
-        fields.sort_by_key(|field| (field.i, field.j));
+        let fields = arbitrary_fields(g, already_played_cards);
 
         let i = (u8::arbitrary(g) % 4) as i8 - 2;
         let j = (u8::arbitrary(g) % 4) as i8 - 2;
@@ -72,6 +50,158 @@ impl quickcheck::Arbitrary for PlayCardInput {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct TurnInput {
+    // Nonempty
+    pub fields: Vec<Field>,
+    pub hand: [Card; 5],
+}
+
+impl quickcheck::Arbitrary for TurnInput {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let mut hand = BTreeSet::new();
+        while hand.len() < 5 {
+            hand.insert(Card::arbitrary(g));
+        }
+        let mut already_played_cards = BTreeSet::<Card>::arbitrary(g);
+        already_played_cards.retain(|card| !hand.contains(card));
+        // Ensure that the list of already played cards is not empty
+        let other_card = loop {
+            let c = Card::arbitrary(g);
+            if !hand.contains(&c) {
+                break c;
+            }
+        };
+        already_played_cards.insert(other_card);
+
+        TurnInput {
+            fields: arbitrary_fields(g, already_played_cards),
+            hand: Vec::from_iter(hand).try_into().unwrap(),
+        }
+    }
+}
+
+/// A board that is reached by playing legal cards, starting with a single card, plus a card
+/// to play on it that may or may not be legal.
+#[derive(Clone, Debug)]
+pub struct ReachablePosition {
+    pub rules: RuleSet,
+    pub board: Board,
+    pub card_to_play: CardToPlay,
+}
+
+impl quickcheck::Arbitrary for ReachablePosition {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        let rules = if bool::arbitrary(g) {
+            RuleSet::STANDARD
+        } else {
+            let board_size = 2 + (u8::arbitrary(g) % 3) as i8;
+            let line_length = 2 + (u8::arbitrary(g) as i8).rem_euclid(board_size - 1);
+            RuleSet::new(board_size, line_length, bool::arbitrary(g)).unwrap()
+        };
+        let mut deck: Vec<Card> = (0..52).map(Card::from_index).collect();
+        for idx in (1..deck.len()).rev() {
+            deck.swap(idx, usize::arbitrary(g) % (idx + 1));
+        }
+        let first_card = Field {
+            i: 0,
+            j: 0,
+            top_card: deck.pop(),
+            hidden_cards: BTreeSet::new(),
+        };
+        let mut board = Board::try_new_with_rules(&[first_card], rules).unwrap();
+
+        let num_cards_played = usize::arbitrary(g) % 30;
+        for _ in 0..num_cards_played {
+            let Some(card) = deck.pop() else { break };
+            let placements = board.placements_for_card(card);
+            if let Some(&placement) = g.choose(&placements) {
+                board = board.play_card(placement).unwrap();
+            }
+        }
+
+        // Legal placements that win cards or are combos are rare when choosing at random, so
+        // they are picked on purpose most of the time
+        let placements: Vec<CardToPlay> = deck
+            .iter()
+            .flat_map(|&card| board.placements_for_card(card))
+            .collect();
+        let winning: Vec<CardToPlay> = placements
+            .iter()
+            .copied()
+            .filter(|&ctp| !board.calculate(ctp).unwrap().cards_won.is_empty())
+            .collect();
+        let combos: Vec<CardToPlay> = placements
+            .iter()
+            .copied()
+            .filter(|ctp| board.get(ctp.i, ctp.j).is_some())
+            .collect();
+        let chosen = match u8::arbitrary(g) % 4 {
+            0 => g.choose(&winning),
+            1 => g.choose(&combos),
+            2 => g.choose(&placements),
+            _ => None,
+        };
+        let card_to_play = match chosen {
+            Some(&card_to_play) => card_to_play,
+            None => {
+                // Also try coordinates just outside of the playable area, and missing king targets
+                let area = board.playable_area();
+                let coordinate = |g: &mut quickcheck::Gen, min: i8, max: i8| {
+                    min - 1 + (u8::arbitrary(g) % (max - min + 3) as u8) as i8
+                };
+                let target_field_for_king_ability = if bool::arbitrary(g) {
+                    g.choose(&board[..]).map(|&(i, j, _)| (i, j))
+                } else {
+                    Option::<(i8, i8)>::arbitrary(g).map(|(i, j)| (i % 8, j % 8))
+                };
+                CardToPlay {
+                    card: deck[usize::arbitrary(g) % deck.len()],
+                    i: coordinate(g, area.i_min, area.i_max),
+                    j: coordinate(g, area.j_min, area.j_max),
+                    target_field_for_king_ability,
+                }
+            }
+        };
+        ReachablePosition {
+            rules,
+            board,
+            card_to_play,
+        }
+    }
+}
+
+// Distributes the cards randomly over a 4 x 4 area, and flips some of them face-down.
+fn arbitrary_fields(g: &mut quickcheck::Gen, cards: BTreeSet<Card>) -> Vec<Field> {
+    let mut cards_on_field = BTreeMap::new();
+    for played_card in cards {
+        let i = (u8::arbitrary(g) % 4) as i8 - 2;
+        let j = (u8::arbitrary(g) % 4) as i8 - 2;
+        cards_on_field
+            .entry((i, j))
+            .or_insert(BTreeSet::new())
+            .insert(played_card);
+    }
+
+    let mut fields = Vec::with_capacity(cards_on_field.len());
+    for ((i, j), mut cards) in cards_on_field {
+        let top_card = if bool::arbitrary(g) {
+            cards.pop_last()
+        } else {
+            None
+        };
+        fields.push(Field {
+            i,
+            j,
+            top_card,
+            hidden_cards: cards,
+        });
+    }
+    fields.sort_by_key(|field| (field.i, field.j));
+
+    fields
+}
+
 #[cfg(test)]
 impl quickcheck::Arbitrary for Suit {
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
@@ -111,3 +241,22 @@ impl quickcheck::Arbitrary for Card {
         }
     }
 }
+
+#[cfg(test)]
+impl quickcheck::Arbitrary for CardToPlay {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        Self {
+            card: Card::arbitrary(g),
+            i: i8::arbitrary(g),
+            j: i8::arbitrary(g),
+            target_field_for_king_ability: Option::arbitrary(g),
+        }
+    }
+}
+
+#[cfg(test)]
+impl quickcheck::Arbitrary for CardsSet {
+    fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+        CardsSet::from_iter(BTreeSet::<Card>::arbitrary(g))
+    }
+}
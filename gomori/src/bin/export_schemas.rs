@@ -0,0 +1,32 @@
+//! Writes the JSON Schemas of the protocol messages into a directory, one file per type.
+//!
+//! ```text
+//! export_schemas <directory>
+//! ```
+//!
+//! The files are named after the types, e.g. `Request.schema.json`.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use gomori::protocol_json_schemas;
+
+const USAGE: &str = "Usage: export_schemas <directory>";
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [directory] = args.as_slice() else {
+        bail!(USAGE);
+    };
+    let directory = PathBuf::from(directory);
+    std::fs::create_dir_all(&directory)
+        .with_context(|| format!("Could not create directory '{}'", directory.display()))?;
+    for (name, schema) in protocol_json_schemas() {
+        let path = directory.join(format!("{}.schema.json", name));
+        let json = serde_json::to_string_pretty(&schema)?;
+        std::fs::write(&path, json + "\n")
+            .with_context(|| format!("Could not write '{}'", path.display()))?;
+        println!("{}", path.display());
+    }
+    Ok(())
+}
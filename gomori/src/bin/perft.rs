@@ -0,0 +1,86 @@
+//! Counts card placements with `gomori::perft()`, to measure and check the rules engine.
+//!
+//! ```text
+//! perft <position> <depth> [--divide]
+//! perft --suite
+//! ```
+//!
+//! The position is a `gomori::Position` string that includes the hand, e.g. `"0,0=7♥ 7♦2♦K♥"`.
+//! With `--divide`, the count for every placement of the first card is printed as well.
+//! With `--suite`, the reference positions in `gomori::PERFT_SUITE` are checked.
+
+use std::str::FromStr;
+use std::time::Instant;
+
+use anyhow::{bail, Context};
+use gomori::{perft, perft_divide, Board, CardsSet, Position, PERFT_SUITE};
+
+const USAGE: &str = "Usage: perft <position> <depth> [--divide]\n       perft --suite";
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.as_slice() {
+        [flag] if flag == "--suite" => run_suite(),
+        [position, depth] => run(position, depth, false),
+        [position, depth, flag] if flag == "--divide" => run(position, depth, true),
+        _ => bail!(USAGE),
+    }
+}
+
+fn parse_position(s: &str) -> anyhow::Result<(Board, CardsSet)> {
+    let position = Position::from_str(s).context("Invalid position")?;
+    let hand = position
+        .hand
+        .context("The position must contain the hand")?;
+    Ok((position.board, hand))
+}
+
+fn run(position: &str, depth: &str, divide: bool) -> anyhow::Result<()> {
+    let (board, hand) = parse_position(position)?;
+    let depth: u32 = depth.parse().context("Invalid depth")?;
+    let start = Instant::now();
+    let count = if divide {
+        let counts = perft_divide(&board, hand, depth);
+        for (ctp, count) in &counts {
+            println!("{}: {}", ctp, count);
+        }
+        counts.iter().map(|&(_, count)| count).sum()
+    } else {
+        perft(&board, hand, depth)
+    };
+    report(depth, count, start);
+    Ok(())
+}
+
+fn run_suite() -> anyhow::Result<()> {
+    let mut num_failed = 0;
+    for &(position, expected_counts) in PERFT_SUITE {
+        println!("{}", position);
+        let (board, hand) = parse_position(position)?;
+        for (depth, &expected) in (1..).zip(expected_counts) {
+            let start = Instant::now();
+            let count = perft(&board, hand, depth);
+            report(depth, count, start);
+            if count != expected {
+                println!("  MISMATCH: expected {}", expected);
+                num_failed += 1;
+            }
+        }
+    }
+    if num_failed > 0 {
+        bail!("{} counts differ from the expected ones", num_failed);
+    }
+    Ok(())
+}
+
+fn report(depth: u32, count: u64, start: Instant) {
+    let elapsed = start.elapsed();
+    let nodes_per_second = count as f64 / elapsed.as_secs_f64().max(1e-9);
+    println!(
+        "  depth {}: {} ({:.3} s, {:.0} nodes/s)",
+        depth,
+        count,
+        elapsed.as_secs_f64(),
+        nodes_per_second
+    );
+}
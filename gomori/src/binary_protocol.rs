@@ -0,0 +1,536 @@
+use std::collections::BTreeSet;
+use std::io::{Read, Write};
+
+use crate::{
+    Card, CardToPlay, CardsSet, Color, CompactField, DecodeError, Field, GameOverReason,
+    HelloResponse, Okay, OpponentTurn, PlayTurnResponse, Request,
+};
+
+/// Messages larger than this are rejected by [`read_frame()`].
+pub const MAX_FRAME_LEN: u32 = 1 << 16;
+
+/// A protocol message with a compact binary encoding, as an alternative to JSON.
+///
+/// It is used after both sides agreed on [`features::BINARY_ENCODING`](crate::features::BINARY_ENCODING)
+/// in the [`Hello`](Request::Hello) exchange, which itself is always JSON. Every message is
+/// a little-endian `u32` length, followed by that many bytes of payload. In the payload:
+///
+/// - Integers are little-endian, and coordinates are single `i8` bytes.
+/// - A card is a byte `4 * rank + suit`, with ranks from 2 (`0`) to ace (`12`) and suits in
+///   the order ♦, ♥, ♠, ♣.
+/// - A set of cards is a `u64` with bit `n` set for the card `n`.
+/// - A field is its coordinates `i` and `j`, followed by a `u64` whose low 52 bits are the
+///   hidden cards, bits 52 to 57 the top card and bit 58 whether there is a top card.
+/// - A card to play is the card and its coordinates, followed by `0`, or `1` and the
+///   coordinates of the king's target.
+/// - Lists are a `u16` length followed by the elements, and strings are UTF-8 lists of bytes.
+/// - Enums start with a tag byte, see the [`BinaryMessage`] implementations.
+pub trait BinaryMessage: Sized {
+    /// Appends the encoded message to `buf`.
+    fn encode(&self, buf: &mut Vec<u8>);
+    /// Decodes a complete message, without the length prefix.
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// Writes a length-prefixed message.
+pub fn write_frame<M: BinaryMessage>(writer: &mut impl Write, message: &M) -> std::io::Result<()> {
+    let mut buf = vec![0; 4];
+    message.encode(&mut buf);
+    let len = u32::try_from(buf.len() - 4).expect("Message too long");
+    buf[..4].copy_from_slice(&len.to_le_bytes());
+    writer.write_all(&buf)?;
+    writer.flush()
+}
+
+/// Reads the payload of a length-prefixed message into `buf`.
+///
+/// Returns `false` if the stream ended before the message started.
+pub fn read_frame(reader: &mut impl Read, buf: &mut Vec<u8>) -> std::io::Result<bool> {
+    let mut len_bytes = [0; 4];
+    match reader.read_exact(&mut len_bytes) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(false),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_le_bytes(len_bytes);
+    if len > MAX_FRAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Message of {} bytes is too long", len),
+        ));
+    }
+    buf.resize(len as usize, 0);
+    reader.read_exact(buf)?;
+    Ok(true)
+}
+
+// Writing primitives
+
+fn put_card(buf: &mut Vec<u8>, card: Card) {
+    buf.push(card.to_index());
+}
+
+fn put_cards_set(buf: &mut Vec<u8>, cards: impl IntoIterator<Item = Card>) {
+    buf.extend_from_slice(&CardsSet::from_iter(cards).bits.to_le_bytes());
+}
+
+fn put_len(buf: &mut Vec<u8>, len: usize) {
+    let len = u16::try_from(len).expect("Too many elements for the binary encoding");
+    buf.extend_from_slice(&len.to_le_bytes());
+}
+
+fn put_string(buf: &mut Vec<u8>, s: &str) {
+    put_len(buf, s.len());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn put_strings(buf: &mut Vec<u8>, strings: &[String]) {
+    put_len(buf, strings.len());
+    for s in strings {
+        put_string(buf, s);
+    }
+}
+
+fn put_fields(buf: &mut Vec<u8>, fields: &[Field]) {
+    put_len(buf, fields.len());
+    for field in fields {
+        buf.push(field.i as u8);
+        buf.push(field.j as u8);
+        buf.extend_from_slice(&CompactField::from(field).bits().to_le_bytes());
+    }
+}
+
+fn put_cards_to_play(buf: &mut Vec<u8>, cards_to_play: &[CardToPlay]) {
+    put_len(buf, cards_to_play.len());
+    for ctp in cards_to_play {
+        put_card(buf, ctp.card);
+        buf.push(ctp.i as u8);
+        buf.push(ctp.j as u8);
+        match ctp.target_field_for_king_ability {
+            None => buf.push(0),
+            Some((tgt_i, tgt_j)) => buf.extend_from_slice(&[1, tgt_i as u8, tgt_j as u8]),
+        }
+    }
+}
+
+fn put_color(buf: &mut Vec<u8>, color: Option<Color>) {
+    buf.push(match color {
+        None => 0,
+        Some(Color::Black) => 1,
+        Some(Color::Red) => 2,
+    });
+}
+
+fn put_game_over_reason(buf: &mut Vec<u8>, reason: GameOverReason) {
+    buf.push(match reason {
+        GameOverReason::BothPlayersSkipped => 0,
+        GameOverReason::DrawPileEmpty => 1,
+        GameOverReason::IllegalMove => 2,
+    });
+}
+
+// Reading primitives
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < n {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn finish<T>(self, value: T) -> Result<T, DecodeError> {
+        if self.bytes.is_empty() {
+            Ok(value)
+        } else {
+            Err(DecodeError::TrailingBytes)
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i8(&mut self) -> Result<i8, DecodeError> {
+        Ok(self.u8()? as i8)
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn card(&mut self) -> Result<Card, DecodeError> {
+        match self.u8()? {
+            index @ 0..=51 => Ok(Card::from_index(index)),
+            index => Err(DecodeError::InvalidCard { index }),
+        }
+    }
+
+    fn hand(&mut self) -> Result<[Card; 5], DecodeError> {
+        Ok([
+            self.card()?,
+            self.card()?,
+            self.card()?,
+            self.card()?,
+            self.card()?,
+        ])
+    }
+
+    fn cards_set(&mut self) -> Result<BTreeSet<Card>, DecodeError> {
+        let bits = self.u64()?;
+        let cards = CardsSet::from_bits(bits).ok_or(DecodeError::InvalidCardsSet { bits })?;
+        Ok(BTreeSet::from_iter(cards))
+    }
+
+    fn list<T>(
+        &mut self,
+        mut element: impl FnMut(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<Vec<T>, DecodeError> {
+        let len = self.u16()?;
+        (0..len).map(|_| element(self)).collect()
+    }
+
+    fn string(&mut self) -> Result<String, DecodeError> {
+        let len = self.u16()?;
+        let bytes = self.take(len.into())?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidString)
+    }
+
+    fn field(&mut self) -> Result<Field, DecodeError> {
+        let i = self.i8()?;
+        let j = self.i8()?;
+        let bits = self.u64()?;
+        let field = CompactField::from_bits(bits).ok_or(DecodeError::InvalidField { bits })?;
+        Ok(field.into_field(i, j))
+    }
+
+    fn card_to_play(&mut self) -> Result<CardToPlay, DecodeError> {
+        let card = self.card()?;
+        let i = self.i8()?;
+        let j = self.i8()?;
+        let target_field_for_king_ability = match self.u8()? {
+            0 => None,
+            1 => Some((self.i8()?, self.i8()?)),
+            tag => {
+                return Err(DecodeError::InvalidTag {
+                    what: "king target",
+                    tag,
+                })
+            }
+        };
+        Ok(CardToPlay {
+            card,
+            i,
+            j,
+            target_field_for_king_ability,
+        })
+    }
+
+    fn color(&mut self) -> Result<Option<Color>, DecodeError> {
+        match self.u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(Color::Black)),
+            2 => Ok(Some(Color::Red)),
+            tag => Err(DecodeError::InvalidTag { what: "color", tag }),
+        }
+    }
+}
+
+/// Tags: `0` = `Hello`, `1` = `NewGame`, `2` = `PlayFirstTurn`, `3` = `PlayTurn`,
+/// `4` = `GameOver`, `5` = `Bye`.
+///
+/// The fields follow in the order of their declaration. Colors are `1` for black and `2` for
+/// red, or `0` for no winner. An opponent turn is `0` for none, `1` and a card for the first
+/// turn, `2` and a list of cards to play, or `3` for a skipped turn. The reason for the end of
+/// the game is `0` if both players skipped, `1` if the draw pile is empty, or `2` for an illegal
+/// move.
+impl BinaryMessage for Request {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Request::Hello {
+                protocol_version,
+                features,
+            } => {
+                buf.push(0);
+                buf.extend_from_slice(&protocol_version.to_le_bytes());
+                put_strings(buf, features);
+            }
+            Request::NewGame { color } => {
+                buf.push(1);
+                put_color(buf, Some(*color));
+            }
+            Request::PlayFirstTurn { cards } => {
+                buf.push(2);
+                cards.iter().for_each(|&card| put_card(buf, card));
+            }
+            Request::PlayTurn {
+                cards,
+                fields,
+                cards_won_by_opponent,
+                opponent_turn,
+            } => {
+                buf.push(3);
+                cards.iter().for_each(|&card| put_card(buf, card));
+                put_fields(buf, fields);
+                put_cards_set(buf, cards_won_by_opponent.iter().copied());
+                match opponent_turn {
+                    None => buf.push(0),
+                    Some(OpponentTurn::FirstTurn { card }) => {
+                        buf.push(1);
+                        put_card(buf, *card);
+                    }
+                    Some(OpponentTurn::Played { cards_to_play }) => {
+                        buf.push(2);
+                        put_cards_to_play(buf, &cards_to_play.0);
+                    }
+                    Some(OpponentTurn::Skipped) => buf.push(3),
+                }
+            }
+            Request::GameOver {
+                winner,
+                cards_won,
+                cards_won_by_opponent,
+                fields,
+                reason,
+            } => {
+                buf.push(4);
+                put_color(buf, *winner);
+                put_cards_set(buf, cards_won.iter().copied());
+                put_cards_set(buf, cards_won_by_opponent.iter().copied());
+                put_fields(buf, fields);
+                put_game_over_reason(buf, *reason);
+            }
+            Request::Bye => buf.push(5),
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader { bytes };
+        let request = match r.u8()? {
+            0 => Request::Hello {
+                protocol_version: r.u32()?,
+                features: r.list(Reader::string)?,
+            },
+            1 => Request::NewGame {
+                color: r.color()?.ok_or(DecodeError::InvalidTag {
+                    what: "color",
+                    tag: 0,
+                })?,
+            },
+            2 => Request::PlayFirstTurn { cards: r.hand()? },
+            3 => Request::PlayTurn {
+                cards: r.hand()?,
+                fields: r.list(Reader::field)?,
+                cards_won_by_opponent: r.cards_set()?,
+                opponent_turn: match r.u8()? {
+                    0 => None,
+                    1 => Some(OpponentTurn::FirstTurn { card: r.card()? }),
+                    2 => Some(OpponentTurn::Played {
+                        cards_to_play: PlayTurnResponse(r.list(Reader::card_to_play)?),
+                    }),
+                    3 => Some(OpponentTurn::Skipped),
+                    tag => {
+                        return Err(DecodeError::InvalidTag {
+                            what: "opponent turn",
+                            tag,
+                        })
+                    }
+                },
+            },
+            4 => Request::GameOver {
+                winner: r.color()?,
+                cards_won: r.cards_set()?,
+                cards_won_by_opponent: r.cards_set()?,
+                fields: r.list(Reader::field)?,
+                reason: match r.u8()? {
+                    0 => GameOverReason::BothPlayersSkipped,
+                    1 => GameOverReason::DrawPileEmpty,
+                    2 => GameOverReason::IllegalMove,
+                    tag => {
+                        return Err(DecodeError::InvalidTag {
+                            what: "game over reason",
+                            tag,
+                        })
+                    }
+                },
+            },
+            5 => Request::Bye,
+            tag => {
+                return Err(DecodeError::InvalidTag {
+                    what: "request",
+                    tag,
+                })
+            }
+        };
+        r.finish(request)
+    }
+}
+
+/// The name, the version and the list of features.
+impl BinaryMessage for HelloResponse {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_string(buf, &self.name);
+        put_string(buf, &self.version);
+        put_strings(buf, &self.features);
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader { bytes };
+        let response = HelloResponse {
+            name: r.string()?,
+            version: r.string()?,
+            features: r.list(Reader::string)?,
+        };
+        r.finish(response)
+    }
+}
+
+/// An empty message.
+impl BinaryMessage for Okay {
+    fn encode(&self, _buf: &mut Vec<u8>) {}
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Reader { bytes }.finish(Okay())
+    }
+}
+
+/// A single card.
+impl BinaryMessage for Card {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_card(buf, *self);
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader { bytes };
+        let card = r.card()?;
+        r.finish(card)
+    }
+}
+
+/// A list of cards to play.
+impl BinaryMessage for PlayTurnResponse {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_cards_to_play(buf, &self.0);
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut r = Reader { bytes };
+        let cards_to_play = r.list(Reader::card_to_play)?;
+        r.finish(PlayTurnResponse(cards_to_play))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::arbitrary::TurnInput;
+
+    // The requests don't implement PartialEq, but their JSON is canonical
+    fn roundtrip<M: BinaryMessage + serde::Serialize>(message: &M) -> bool {
+        let mut buf = Vec::new();
+        message.encode(&mut buf);
+        let decoded = M::decode(&buf).unwrap();
+        serde_json::to_string(&decoded).unwrap() == serde_json::to_string(message).unwrap()
+    }
+
+    quickcheck! {
+        fn play_turn_roundtrip(input: TurnInput, cards_to_play: Vec<CardToPlay>) -> bool {
+            let cards_won = BTreeSet::from_iter(input.fields.iter().filter_map(|f| f.top_card));
+            let opponent_turn = OpponentTurn::Played {
+                cards_to_play: PlayTurnResponse(cards_to_play.clone()),
+            };
+            let play_turn = Request::PlayTurn {
+                cards: input.hand,
+                fields: input.fields.clone(),
+                cards_won_by_opponent: cards_won.clone(),
+                opponent_turn: Some(opponent_turn),
+            };
+            let game_over = Request::GameOver {
+                winner: Some(Color::Red),
+                cards_won: BTreeSet::new(),
+                cards_won_by_opponent: cards_won,
+                fields: input.fields,
+                reason: GameOverReason::DrawPileEmpty,
+            };
+            roundtrip(&play_turn) && roundtrip(&game_over) && roundtrip(&PlayTurnResponse(cards_to_play))
+        }
+    }
+
+    #[test]
+    fn frames() {
+        let requests = [
+            Request::Hello {
+                protocol_version: 2,
+                features: vec![String::from("binary_encoding")],
+            },
+            Request::NewGame {
+                color: Color::Black,
+            },
+            Request::PlayFirstTurn {
+                cards: [0, 5, 10, 15, 51].map(Card::from_index),
+            },
+            Request::Bye,
+        ];
+        let mut stream = Vec::new();
+        for request in &requests {
+            assert!(roundtrip(request));
+            write_frame(&mut stream, request).unwrap();
+        }
+        // One tag byte plus a black color
+        assert_eq!(&stream[stream.len() - 5..], [1, 0, 0, 0, 5]);
+
+        let mut reader = stream.as_slice();
+        let mut buf = Vec::new();
+        for request in &requests {
+            assert!(read_frame(&mut reader, &mut buf).unwrap());
+            let decoded = Request::decode(&buf).unwrap();
+            assert_eq!(
+                serde_json::to_string(&decoded).unwrap(),
+                serde_json::to_string(request).unwrap()
+            );
+        }
+        assert!(!read_frame(&mut reader, &mut buf).unwrap());
+    }
+
+    #[test]
+    fn invalid_messages() {
+        assert_eq!(
+            Card::decode(&[52]),
+            Err(DecodeError::InvalidCard { index: 52 })
+        );
+        assert_eq!(Card::decode(&[]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(Card::decode(&[0, 0]), Err(DecodeError::TrailingBytes));
+        assert_eq!(
+            PlayTurnResponse::decode(&[1, 0, 0, 0, 0, 2]).unwrap_err(),
+            DecodeError::InvalidTag {
+                what: "king target",
+                tag: 2
+            }
+        );
+        let bits = 1u64 << 60;
+        let mut play_turn = vec![3, 0, 1, 2, 3, 4, 1, 0, 0, 0];
+        play_turn.extend_from_slice(&bits.to_le_bytes());
+        assert_eq!(
+            Request::decode(&play_turn).unwrap_err(),
+            DecodeError::InvalidField { bits }
+        );
+        let mut too_long = &(MAX_FRAME_LEN + 1).to_le_bytes()[..];
+        assert!(read_frame(&mut too_long, &mut Vec::new()).is_err());
+    }
+}
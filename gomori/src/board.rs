@@ -1,22 +1,45 @@
 mod bbox;
 mod bitboard;
 mod compact_field;
+mod symmetry;
+mod threats;
+mod zobrist;
 
+use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 
 pub use bbox::*;
 pub use bitboard::*;
 pub use compact_field::*;
+pub use symmetry::*;
+pub use threats::*;
 
-use crate::{Card, CardToPlay, CardsSet, Field, IllegalCardPlayed, Rank, Suit};
+use crate::{
+    Card, CardToPlay, CardsSet, Field, IllegalCardPlayed, InvalidBoard, Rank, RuleSet, Suit,
+};
 
-pub const BOARD_SIZE: i8 = 4;
+/// The board size of the [standard rules](RuleSet::STANDARD).
+pub const BOARD_SIZE: i8 = RuleSet::STANDARD.board_size();
+
+/// The largest absolute value of a coordinate on a [`Board`].
+///
+/// Since there are only 52 cards, a game can't move further away from the first card at
+/// `(0, 0)`. Boards with coordinates beyond this are invalid, and cards can't be played there.
+pub const MAX_COORDINATE: i8 = 52;
+
+fn coordinate_in_range(coordinate: i8) -> bool {
+    (-MAX_COORDINATE..=MAX_COORDINATE).contains(&coordinate)
+}
 
 /// Represents a board with at least one card on it.
 ///
 /// The idea is that a list of [`Field`]s is used in the communication between judge and bots,
 /// but is then converted into this type for performing the actual tasks like e.g. [determining
 /// whether a card can be played](Board::possible_to_play_card).
+///
+/// Two boards are equal if they have the same cards at the same coordinates, regardless of
+/// the order in which the fields are stored. The [`Hash`] impl is based on the
+/// [Zobrist key](Board::zobrist_key), so boards can be used as keys in transposition tables.
 //
 // Because after the first move, there is at least one card on it,
 // the minimum and maximum coordinates always exist.
@@ -37,6 +60,10 @@ pub struct Board {
     bbox: BoundingBox,
     /// All the diamond/heart/spade/club cards on the board.
     bitboards: [BitBoard; 4],
+    /// The XOR of the Zobrist keys of all fields.
+    zobrist: u64,
+    /// The rules that are used for playing cards on this board.
+    rules: RuleSet,
 }
 
 #[derive(Clone)]
@@ -48,6 +75,33 @@ struct Diff {
     new_card_j: i8,
 }
 
+/// The maximum number of fields that can be changed by playing a single card.
+///
+/// Up to 12 fields can be won (three in each of the four lines going through the new card),
+/// up to 4 fields can be flipped, plus the field that the new card is placed on.
+const MAX_CHANGED_FIELDS: usize = 17;
+
+/// The information needed to take back a card played with [`Board::make()`].
+///
+/// Pass it to [`Board::unmake()`] to restore the board to the state before the card was played.
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+#[derive(Clone, Debug)]
+pub struct Undo {
+    /// The cards that were won as a result of playing this card
+    pub cards_won: CardsSet,
+    /// Should another card be played?
+    pub combo: bool,
+    bitboards_center: (i8, i8),
+    bbox: BoundingBox,
+    bitboards: [BitBoard; 4],
+    zobrist: u64,
+    /// Set if the card was placed on a field that didn't exist before.
+    created_field: Option<(i8, i8)>,
+    /// The previous contents of all fields that were changed or removed.
+    changed_fields: [(i8, i8, CompactField); MAX_CHANGED_FIELDS],
+    num_changed_fields: usize,
+}
+
 /// The effects that playing a card would have.
 ///
 /// Returned by [`Board::calculate()`].
@@ -65,42 +119,110 @@ pub struct CalculatedEffects<'a> {
 impl Board {
     /// Creates a new board from a list of [`Field`]s.
     ///
-    /// Panics if the fields are (obviously) invalid, e.g. if it is larger than 4 x 4.
+    /// Panics if the fields are invalid, see [`Self::try_new()`].
     pub fn new(fields: &[Field]) -> Self {
-        Self::from_fields_list(
-            fields
-                .iter()
-                .map(|f| (f.i, f.j, CompactField::from(f)))
-                .collect(),
-        )
+        Self::try_new(fields).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new board from a list of [`Field`]s, or returns an error if they are invalid.
+    ///
+    /// The fields are invalid if there are none, if they don't fit into a 4 x 4 area, if two
+    /// fields have the same coordinates, if a field has no cards, or if a card appears twice.
+    pub fn try_new(fields: &[Field]) -> Result<Self, InvalidBoard> {
+        Self::try_new_with_rules(fields, RuleSet::STANDARD)
+    }
+
+    /// Like [`Self::try_new()`], but for a variant of the rules.
+    ///
+    /// The fields must fit into an area of the board size of the rules.
+    pub fn try_new_with_rules(fields: &[Field], rules: RuleSet) -> Result<Self, InvalidBoard> {
+        let mut fields_list = Vec::with_capacity(fields.len());
+        let mut all_cards = CardsSet::new();
+        for field in fields {
+            // Duplicate cards within one field would be lost in the conversion to CompactField
+            for &card in field.top_card.iter().chain(&field.hidden_cards) {
+                if all_cards.contains(card) {
+                    return Err(InvalidBoard::DuplicateCard { card });
+                }
+                all_cards = all_cards.insert(card);
+            }
+            fields_list.push((field.i, field.j, CompactField::from(field)));
+        }
+        Self::try_from_fields_list_with_rules(fields_list, rules)
     }
 
     /// Creates a new board from a list of [`CompactField`]s.
     ///
-    /// Panics if the fields are (obviously) invalid, e.g. if it is larger than 4 x 4.
+    /// Panics if the fields are invalid, see [`Self::try_new()`].
     pub fn from_fields_list(fields: Vec<(i8, i8, CompactField)>) -> Self {
-        assert!(!fields.is_empty());
+        Self::try_from_fields_list(fields).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Creates a new board from a list of [`CompactField`]s, or returns an error if they are
+    /// invalid, see [`Self::try_new()`].
+    pub fn try_from_fields_list(fields: Vec<(i8, i8, CompactField)>) -> Result<Self, InvalidBoard> {
+        Self::try_from_fields_list_with_rules(fields, RuleSet::STANDARD)
+    }
+
+    /// Like [`Self::try_from_fields_list()`], but for a variant of the rules.
+    pub fn try_from_fields_list_with_rules(
+        fields: Vec<(i8, i8, CompactField)>,
+        rules: RuleSet,
+    ) -> Result<Self, InvalidBoard> {
+        if fields.is_empty() {
+            return Err(InvalidBoard::NoFields);
+        }
         let bitboards_center = (fields[0].0, fields[0].1);
         let mut bbox = BoundingBox::singleton(fields[0].0, fields[0].1);
-        let mut bitboards = [BitBoard::empty_board_centered_at(bitboards_center); 4];
-
-        for field in &fields {
-            debug_assert!(field.2.top_card().is_some() || !field.2.hidden_cards().is_empty());
-            bbox.update(field.0, field.1);
-            if let Some(Card { suit, .. }) = field.2.top_card() {
-                bitboards[suit as usize] = bitboards[suit as usize].insert(field.0, field.1);
+        let mut all_cards = CardsSet::new();
+        for (idx, &(i, j, field)) in fields.iter().enumerate() {
+            if field.is_empty() {
+                return Err(InvalidBoard::EmptyField { i, j });
             }
+            if fields[..idx]
+                .iter()
+                .any(|&(i_other, j_other, _)| (i_other, j_other) == (i, j))
+            {
+                return Err(InvalidBoard::DuplicateCoordinates { i, j });
+            }
+            if let Some(card) = (all_cards & field.all_cards()).into_iter().next() {
+                return Err(InvalidBoard::DuplicateCard { card });
+            }
+            all_cards |= field.all_cards();
+            bbox.update(i, j);
         }
 
-        assert!(bbox.size_i() <= BOARD_SIZE as u8);
-        assert!(bbox.size_j() <= BOARD_SIZE as u8);
+        // Computed without BoundingBox::size_i(), which would overflow for far apart fields
+        let size = |min: i8, max: i8| i16::from(max) - i16::from(min) + 1;
+        if size(bbox.i_min, bbox.i_max) > i16::from(rules.board_size())
+            || size(bbox.j_min, bbox.j_max) > i16::from(rules.board_size())
+        {
+            return Err(InvalidBoard::TooLarge);
+        }
+        if let Some(&(i, j, _)) = fields
+            .iter()
+            .find(|&&(i, j, _)| !coordinate_in_range(i) || !coordinate_in_range(j))
+        {
+            return Err(InvalidBoard::CoordinatesOutOfRange { i, j });
+        }
+
+        let mut bitboards = [BitBoard::empty_board_centered_at(bitboards_center); 4];
+        let mut zobrist = 0;
+        for &(i, j, field) in &fields {
+            zobrist ^= zobrist::field_key(i, j, field);
+            if let Some(Card { suit, .. }) = field.top_card() {
+                bitboards[suit as usize] = bitboards[suit as usize].insert(i, j);
+            }
+        }
 
-        Self {
+        Ok(Self {
             fields,
             bitboards_center,
             bbox,
             bitboards,
-        }
+            zobrist,
+            rules,
+        })
     }
 
     /// Calculate playing a card and return the effects that this would have.
@@ -150,25 +272,17 @@ impl Board {
 
         let won: BitBoard = {
             // A bitboard representation of all cards of the same suit as the newly
-            // placed card. If there is a line of 4 cards, it must be cards of this
+            // placed card. If there is a new line of cards, it must be cards of this
             // suit.
             let cards_of_same_suit = self.bitboards[card.suit as usize]
                 .insert(i, j)
                 .difference(flipped);
             cards_of_same_suit
-                .lines_going_through_point(i, j)
+                .lines_going_through_point_with_rules(i, j, self.rules)
                 .remove(i, j)
         };
 
-        let cards_won = {
-            let mut set = CardsSet::new();
-            for &(i, j, field) in &self.fields {
-                if won.contains(i, j) {
-                    set |= field.all_cards();
-                }
-            }
-            set
-        };
+        let cards_won = self.cards_on(won);
 
         Ok(CalculatedEffects {
             board: self,
@@ -189,15 +303,77 @@ impl Board {
         self.calculate(card_to_play).map(CalculatedEffects::execute)
     }
 
+    /// Plays a card by modifying the board in-place.
+    ///
+    /// Like [`play_card()`](Board::play_card), but instead of creating a new board, the changes
+    /// are applied to this board, and the returned [`Undo`] allows reverting them with
+    /// [`unmake()`](Board::unmake). This avoids allocations, which is useful for searching
+    /// through many possible moves.
+    ///
+    /// If the card cannot be played, the board is not modified.
+    pub fn make(&mut self, card_to_play: &CardToPlay) -> Result<Undo, IllegalCardPlayed> {
+        let CalculatedEffects {
+            diff,
+            cards_won,
+            combo,
+            ..
+        } = self.calculate(*card_to_play)?;
+        Ok(diff.apply_in_place(self, cards_won, combo))
+    }
+
+    /// Reverts the changes made by [`make()`](Board::make).
+    ///
+    /// Moves must be taken back in the reverse order in which they were made, and only on
+    /// the board that they were made on.
+    pub fn unmake(&mut self, undo: Undo) {
+        if let Some((i, j)) = undo.created_field {
+            let idx = self
+                .fields
+                .iter()
+                .position(|&(i_field, j_field, _)| (i_field, j_field) == (i, j))
+                .expect("Undo does not match this board");
+            self.fields.swap_remove(idx);
+        }
+        for &(i, j, field) in &undo.changed_fields[..undo.num_changed_fields] {
+            match self
+                .fields
+                .iter_mut()
+                .find(|(i_field, j_field, _)| (*i_field, *j_field) == (i, j))
+            {
+                Some(entry) => entry.2 = field,
+                None => self.fields.push((i, j, field)),
+            }
+        }
+        self.bitboards_center = undo.bitboards_center;
+        self.bbox = undo.bbox;
+        self.bitboards = undo.bitboards;
+        self.zobrist = undo.zobrist;
+    }
+
     /// The smallest area enclosing the cards currently on the board.
     ///
-    /// This is always smaller than or equal to [`BOARD_SIZE`] x [`BOARD_SIZE`].
+    /// This is always smaller than or equal to the board size of the [rules](Self::rules),
+    /// i.e. [`BOARD_SIZE`] x [`BOARD_SIZE`] for the standard rules.
     ///
     /// See [`Self::playable_area()`] for the area where cards may be placed.
     pub fn bbox(&self) -> BoundingBox {
         self.bbox
     }
 
+    /// The rules that are used for playing cards on this board.
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// A 64-bit hash of the cards on the board and their coordinates.
+    ///
+    /// This is a [Zobrist key](https://en.wikipedia.org/wiki/Zobrist_hashing), which is
+    /// updated incrementally when playing cards. Equal boards always have the same key,
+    /// and the key is stable across program runs.
+    pub fn zobrist_key(&self) -> u64 {
+        self.zobrist
+    }
+
     /// The coordinates where a card may be placed.
     ///
     /// Trying to play a card outside of these bounds will result in an
@@ -207,11 +383,12 @@ impl Board {
     /// e.g. if there's only a single card on the board so far, the area
     /// will be the 7 x 7 area centered on that card.
     pub fn playable_area(&self) -> BoundingBox {
+        let board_size = self.rules.board_size();
         BoundingBox {
-            i_min: self.bbox.i_max - BOARD_SIZE + 1,
-            j_min: self.bbox.j_max - BOARD_SIZE + 1,
-            i_max: self.bbox.i_min + BOARD_SIZE - 1,
-            j_max: self.bbox.j_min + BOARD_SIZE - 1,
+            i_min: (self.bbox.i_max - board_size + 1).max(-MAX_COORDINATE),
+            j_min: (self.bbox.j_max - board_size + 1).max(-MAX_COORDINATE),
+            i_max: (self.bbox.i_min + board_size - 1).min(MAX_COORDINATE),
+            j_max: (self.bbox.j_min + board_size - 1).min(MAX_COORDINATE),
         }
     }
 
@@ -239,7 +416,8 @@ impl Board {
     ///
     /// This is a bit more efficient than checking [`Self::locations_for_card()`].
     pub fn possible_to_play_card(&self, card: Card) -> bool {
-        if self.fields.len() < 16 {
+        let board_size = usize::from(self.rules.board_size().unsigned_abs());
+        if self.fields.len() < board_size * board_size {
             return true;
         }
         for (_, _, field) in &self.fields {
@@ -282,6 +460,36 @@ impl Board {
         bitboard
     }
 
+    /// Returns all the ways in which the given card can be played.
+    ///
+    /// This is like [`Self::locations_for_card()`], except that a king which is played on top
+    /// of another card is returned once for every possible target of its ability.
+    pub fn placements_for_card(&self, card: Card) -> Vec<CardToPlay> {
+        let mut placements = Vec::new();
+        for (i, j) in self.locations_for_card(card) {
+            let ctp = CardToPlay {
+                card,
+                i,
+                j,
+                target_field_for_king_ability: None,
+            };
+            if card.rank == Rank::King && self.get(i, j).is_some() {
+                // The king may target any face-up card, or itself
+                for &(tgt_i, tgt_j, field) in &self.fields {
+                    if field.top_card().is_some() || (tgt_i, tgt_j) == (i, j) {
+                        placements.push(CardToPlay {
+                            target_field_for_king_ability: Some((tgt_i, tgt_j)),
+                            ..ctp
+                        });
+                    }
+                }
+            } else {
+                placements.push(ctp);
+            }
+        }
+        placements
+    }
+
     /// Returns a [`CompactField`] if there are any cards at the given coordinate.
     pub fn get(&self, i: i8, j: i8) -> Option<CompactField> {
         for &(i_field, j_field, compact_field) in &self.fields {
@@ -293,10 +501,13 @@ impl Board {
     }
 
     pub fn is_in_bounds(&self, i: i8, j: i8) -> bool {
-        (i.checked_sub(self.bbox.i_min).map(|diff| diff < BOARD_SIZE)).unwrap_or(false)
-            && (self.bbox.i_max.checked_sub(i).map(|diff| diff < BOARD_SIZE)).unwrap_or(false)
-            && (j.checked_sub(self.bbox.j_min).map(|diff| diff < BOARD_SIZE)).unwrap_or(false)
-            && (self.bbox.j_max.checked_sub(j).map(|diff| diff < BOARD_SIZE)).unwrap_or(false)
+        let board_size = self.rules.board_size();
+        coordinate_in_range(i)
+            && coordinate_in_range(j)
+            && (i.checked_sub(self.bbox.i_min).map(|diff| diff < board_size)).unwrap_or(false)
+            && (self.bbox.i_max.checked_sub(i).map(|diff| diff < board_size)).unwrap_or(false)
+            && (j.checked_sub(self.bbox.j_min).map(|diff| diff < board_size)).unwrap_or(false)
+            && (self.bbox.j_max.checked_sub(j).map(|diff| diff < board_size)).unwrap_or(false)
     }
 
     pub fn to_fields_vec(&self) -> Vec<Field> {
@@ -320,6 +531,17 @@ impl Board {
         fields_vec
     }
 
+    // All cards, including hidden ones, on the given fields.
+    fn cards_on(&self, fields: BitBoard) -> CardsSet {
+        let mut set = CardsSet::new();
+        for &(i, j, field) in &self.fields {
+            if fields.contains(i, j) {
+                set |= field.all_cards();
+            }
+        }
+        set
+    }
+
     // Internal helper function to compute fields where the top cards are flipped face-down.
     //
     // Note: The result also contains empty fields and fields
@@ -377,27 +599,115 @@ impl Deref for Board {
     }
 }
 
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.zobrist == other.zobrist
+            && self.rules == other.rules
+            && self.fields.len() == other.fields.len()
+            && self
+                .fields
+                .iter()
+                .all(|&(i, j, field)| other.get(i, j) == Some(field))
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
+}
+
 impl<'a> CalculatedEffects<'a> {
     /// Apply the computed changes from playing the card.
     pub fn execute(self) -> Board {
         self.diff.apply(self.board)
     }
+
+    /// The fields that are turned face-down by the ability of a jack, queen or king.
+    ///
+    /// If a king targets its own field, this includes the field of the new card.
+    pub fn flipped(&self) -> BitBoard {
+        self.diff.flipped_fields(self.board)
+    }
+
+    /// The fields that are won, and therefore removed from the board.
+    ///
+    /// The field of the new card is never won.
+    pub fn won(&self) -> BitBoard {
+        self.diff.won
+    }
+
+    /// The coordinates `(i, j)` of the new card.
+    pub fn new_card_coordinates(&self) -> (i8, i8) {
+        (self.diff.new_card_i, self.diff.new_card_j)
+    }
 }
 
 impl Diff {
+    // The ability of a jack or queen applies to all adjacent coordinates, but only those with
+    // a card on them are actually flipped.
+    fn flipped_fields(&self, board: &Board) -> BitBoard {
+        let mut flipped = self.flipped;
+        for (i, j) in self.flipped {
+            if board.get(i, j).is_none() {
+                flipped = flipped.remove(i, j);
+            }
+        }
+        flipped
+    }
+
     fn apply(self, board: &Board) -> Board {
-        let mut new_fields = Vec::with_capacity(board.fields.len() + 1);
+        let mut fields = Vec::with_capacity(board.fields.len() + 1);
+        fields.extend_from_slice(&board.fields);
+        let mut new_board = Board {
+            fields,
+            bitboards_center: board.bitboards_center,
+            bbox: board.bbox,
+            bitboards: board.bitboards,
+            zobrist: board.zobrist,
+            rules: board.rules,
+        };
+        self.apply_in_place(&mut new_board, CardsSet::new(), false);
+        new_board
+    }
+
+    // The cards_won and combo arguments are only passed through to the Undo.
+    fn apply_in_place(self, board: &mut Board, cards_won: CardsSet, combo: bool) -> Undo {
+        let mut undo = Undo {
+            cards_won,
+            combo,
+            bitboards_center: board.bitboards_center,
+            bbox: board.bbox,
+            bitboards: board.bitboards,
+            zobrist: board.zobrist,
+            created_field: None,
+            changed_fields: [(0, 0, CompactField::new()); MAX_CHANGED_FIELDS],
+            num_changed_fields: 0,
+        };
+        let mut zobrist = board.zobrist;
+        let mut record_change = |i: i8, j: i8, field: CompactField| {
+            undo.changed_fields[undo.num_changed_fields] = (i, j, field);
+            undo.num_changed_fields += 1;
+        };
+
         let mut bbox = BoundingBox::singleton(self.new_card_i, self.new_card_j);
         let bitboards_center = (self.new_card_i, self.new_card_j);
         let mut bitboards = [BitBoard::empty_board_centered_at(bitboards_center); 4];
         let mut field_for_new_card_already_exists = false;
 
-        // Copy over the fields while applying changes and updating derived
-        // data (bbox and bitboards)
-        for &(i, j, mut field) in board.fields.iter() {
+        // Apply changes to the fields while updating derived data (bbox and bitboards)
+        let mut idx = 0;
+        while idx < board.fields.len() {
+            let (i, j, old_field) = board.fields[idx];
             if self.won.contains(i, j) {
+                record_change(i, j, old_field);
+                zobrist ^= zobrist::field_key(i, j, old_field);
+                board.fields.swap_remove(idx);
                 continue;
             }
+            let mut field = old_field;
             if (i, j) == (self.new_card_i, self.new_card_j) {
                 field = field.place_card(self.new_card);
                 field_for_new_card_already_exists = true;
@@ -405,7 +715,12 @@ impl Diff {
             if self.flipped.contains(i, j) {
                 field = field.turn_face_down()
             }
-            new_fields.push((i, j, field));
+            if field != old_field {
+                record_change(i, j, old_field);
+                zobrist ^= zobrist::field_key(i, j, old_field) ^ zobrist::field_key(i, j, field);
+                board.fields[idx].2 = field;
+            }
+            idx += 1;
 
             // Update derived data
             bbox.update(i, j);
@@ -423,24 +738,79 @@ impl Diff {
                 bitboards[self.new_card.suit as usize] =
                     bitboards[self.new_card.suit as usize].insert(self.new_card_i, self.new_card_j);
             }
-            new_fields.push((self.new_card_i, self.new_card_j, new_field));
+            board
+                .fields
+                .push((self.new_card_i, self.new_card_j, new_field));
+            zobrist ^= zobrist::field_key(self.new_card_i, self.new_card_j, new_field);
+            undo.created_field = Some((self.new_card_i, self.new_card_j));
         }
 
-        Board {
-            bitboards_center,
-            fields: new_fields,
-            bbox,
-            bitboards,
+        board.bitboards_center = bitboards_center;
+        board.bbox = bbox;
+        board.bitboards = bitboards;
+        board.zobrist = zobrist;
+        undo
+    }
+}
+
+// Human-readable formats get the fields in the same format as in the protocol, binary formats
+// get the compact fields. In both cases, the fields are sorted by their coordinates.
+#[cfg(feature = "engine-serde")]
+mod serialization {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct BoardRepr<F> {
+        fields: Vec<F>,
+        #[serde(default)]
+        rules: RuleSet,
+    }
+
+    impl Serialize for Board {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                BoardRepr {
+                    fields: self.to_fields_vec(),
+                    rules: self.rules,
+                }
+                .serialize(serializer)
+            } else {
+                let mut fields = self.fields.clone();
+                fields.sort_by_key(|&(i, j, _)| (i, j));
+                BoardRepr {
+                    fields,
+                    rules: self.rules,
+                }
+                .serialize(serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Board {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                let BoardRepr { fields, rules } = BoardRepr::<Field>::deserialize(deserializer)?;
+                Board::try_new_with_rules(&fields, rules).map_err(D::Error::custom)
+            } else {
+                let BoardRepr { fields, rules } =
+                    BoardRepr::<(i8, i8, CompactField)>::deserialize(deserializer)?;
+                Board::try_from_fields_list_with_rules(fields, rules).map_err(D::Error::custom)
+            }
         }
     }
 }
 
 #[cfg(feature = "python")]
 mod python {
-    use pyo3::{pyclass, pymethods, Py};
+    use pyo3::{pyclass, pyclass::CompareOp, pymethods, IntoPy, Py, PyObject, PyRef, Python};
 
     use super::*;
-    use crate::{BoundingBox, CardToPlay, CompactField, IllegalCardPlayed};
+    use crate::{
+        BoundingBox, CardToPlay, CompactField, IllegalCardPlayed, IllegalMove, InvalidBoard,
+        PlayTurnResponse, PositionFromStrErr,
+    };
 
     #[pyclass]
     pub struct CalculatedEffects {
@@ -457,8 +827,55 @@ mod python {
     #[pymethods]
     impl Board {
         #[new]
-        fn py_from_fields_list(fields: Vec<(i8, i8, CompactField)>) -> Self {
-            Self::from_fields_list(fields)
+        fn py_from_fields_list(fields: Vec<(i8, i8, CompactField)>) -> Result<Self, InvalidBoard> {
+            Self::try_from_fields_list(fields)
+        }
+
+        fn __hash__(&self) -> u64 {
+            self.zobrist_key()
+        }
+
+        fn __richcmp__(&self, other: PyRef<Board>, op: CompareOp, py: Python) -> PyObject {
+            match op {
+                CompareOp::Eq => (*self == *other).into_py(py),
+                CompareOp::Ne => (*self != *other).into_py(py),
+                _ => py.NotImplemented(),
+            }
+        }
+
+        #[pyo3(name = "zobrist_key")]
+        fn py_zobrist_key(&self) -> u64 {
+            self.zobrist_key()
+        }
+
+        #[pyo3(name = "to_position_string")]
+        fn py_to_position_string(&self) -> String {
+            self.to_position_string()
+        }
+
+        #[pyo3(name = "render", signature = (highlight = None, colors = false))]
+        fn py_render(&self, highlight: Option<BitBoard>, colors: bool) -> String {
+            let renderer = self.render().with_colors(colors);
+            match highlight {
+                Some(highlight) => renderer.highlight(highlight).to_string(),
+                None => renderer.to_string(),
+            }
+        }
+
+        #[pyo3(name = "to_svg")]
+        fn py_to_svg(&self) -> String {
+            self.to_svg()
+        }
+
+        #[pyo3(name = "turn_to_svg")]
+        fn py_turn_to_svg(&self, turn: &PlayTurnResponse) -> Result<String, IllegalMove> {
+            self.turn_to_svg(turn)
+        }
+
+        #[staticmethod]
+        #[pyo3(name = "from_position_string")]
+        fn py_from_position_string(s: &str) -> Result<Board, PositionFromStrErr> {
+            Self::from_position_string(s)
         }
 
         #[pyo3(name = "to_fields")]
@@ -489,6 +906,16 @@ mod python {
             self.play_card(card_to_play)
         }
 
+        #[pyo3(name = "make")]
+        fn py_make(&mut self, card_to_play: CardToPlay) -> Result<Undo, IllegalCardPlayed> {
+            self.make(&card_to_play)
+        }
+
+        #[pyo3(name = "unmake")]
+        fn py_unmake(&mut self, undo: Undo) {
+            self.unmake(undo)
+        }
+
         #[pyo3(name = "bbox")]
         fn py_bbox(&self) -> BoundingBox {
             self.bbox()
@@ -534,6 +961,11 @@ mod python {
             self.combo_locations_for_card(card)
         }
 
+        #[pyo3(name = "placements_for_card")]
+        fn py_placements_for_card(&self, card: Card) -> Vec<CardToPlay> {
+            self.placements_for_card(card)
+        }
+
         #[pyo3(name = "get")]
         fn py_get(&self, i: i8, j: i8) -> Option<CompactField> {
             self.get(i, j)
@@ -545,11 +977,41 @@ mod python {
         }
     }
 
+    #[pymethods]
+    impl Undo {
+        #[getter]
+        #[pyo3(name = "cards_won")]
+        fn py_cards_won(&self) -> CardsSet {
+            self.cards_won
+        }
+
+        #[getter]
+        #[pyo3(name = "combo")]
+        fn py_combo(&self) -> bool {
+            self.combo
+        }
+    }
+
     #[pymethods]
     impl CalculatedEffects {
         fn execute(&self) -> Board {
             pyo3::Python::with_gil(|py| self.diff.clone().apply(&self.board.borrow(py)))
         }
+
+        #[getter]
+        fn flipped(&self) -> BitBoard {
+            pyo3::Python::with_gil(|py| self.diff.flipped_fields(&self.board.borrow(py)))
+        }
+
+        #[getter]
+        fn won(&self) -> BitBoard {
+            self.diff.won
+        }
+
+        #[getter]
+        fn new_card_coordinates(&self) -> (i8, i8) {
+            (self.diff.new_card_i, self.diff.new_card_j)
+        }
     }
 }
 
@@ -564,6 +1026,8 @@ mod tests {
     use quickcheck::quickcheck;
 
     use super::*;
+    #[cfg(feature = "engine-serde")]
+    use crate::arbitrary::TurnInput;
     use crate::{arbitrary::PlayCardInput, card, CardToPlay};
 
     quickcheck! {
@@ -580,6 +1044,70 @@ mod tests {
             }
             more_than_zero_locations == board.possible_to_play_card(input.card_to_play.card)
         }
+
+        fn make_and_unmake(input: PlayCardInput) -> bool {
+            let board = Board::new(&input.fields);
+            let mut made = board.clone();
+            match made.make(&input.card_to_play) {
+                Ok(undo) => {
+                    let played = board.play_card(input.card_to_play).unwrap();
+                    let same_as_play_card = made.to_fields_vec() == played.to_fields_vec()
+                        && made.bitboards == played.bitboards;
+                    made.unmake(undo);
+                    same_as_play_card
+                        && made == board
+                        && made.zobrist_key() == board.zobrist_key()
+                        && made.to_fields_vec() == board.to_fields_vec()
+                        && made.bitboards == board.bitboards
+                        && made.bbox.size_i() == board.bbox.size_i()
+                        && made.bbox.size_j() == board.bbox.size_j()
+                }
+                Err(_) => made.to_fields_vec() == board.to_fields_vec(),
+            }
+        }
+    }
+
+    quickcheck! {
+        fn zobrist_key_is_updated_incrementally(input: PlayCardInput) -> bool {
+            let board = Board::new(&input.fields);
+            match board.play_card(input.card_to_play) {
+                Ok(played) => {
+                    let recomputed = Board::from_fields_list(played.fields.clone());
+                    played.zobrist_key() == recomputed.zobrist_key() && played == recomputed
+                }
+                Err(_) => true,
+            }
+        }
+    }
+
+    #[test]
+    fn equality_ignores_field_order() {
+        let fields = vec![
+            (0, 0, CompactField::new().place_card(card!("4♦"))),
+            (
+                0,
+                1,
+                CompactField::new().place_card(card!("5♠")).turn_face_down(),
+            ),
+            (
+                1,
+                1,
+                CompactField::new()
+                    .place_card(card!("2♣"))
+                    .place_card(card!("A♥")),
+            ),
+        ];
+        let mut reversed = fields.clone();
+        reversed.reverse();
+        let board_1 = Board::from_fields_list(fields.clone());
+        let board_2 = Board::from_fields_list(reversed);
+        assert_eq!(board_1, board_2);
+        assert_eq!(board_1.zobrist_key(), board_2.zobrist_key());
+
+        // Same cards, but the face-down card is in a different place
+        let mut moved = fields;
+        moved[1].1 = 2;
+        assert_ne!(board_1, Board::from_fields_list(moved));
     }
 
     #[test]
@@ -619,7 +1147,12 @@ mod tests {
                 target_field_for_king_ability: None,
             })
             .unwrap();
-        assert!(plan.diff.flipped.is_empty());
+        assert!(plan.flipped().is_empty());
+        assert_eq!(
+            Vec::from_iter(plan.won()),
+            vec![(-1, -2), (-1, -1), (-1, 0)]
+        );
+        assert_eq!(plan.new_card_coordinates(), (-1, -3));
         assert_eq!(
             plan.cards_won,
             CardsSet::from_iter([card!("4♦"), card!("5♦"), card!("6♦")])
@@ -666,4 +1199,196 @@ mod tests {
         assert!(plan.diff.flipped.is_empty());
         assert!(!plan.diff.won.is_empty());
     }
+
+    #[test]
+    fn flipped_fields_of_face_cards() {
+        let board = Board::from_position_string("0,0=7♦/0,1=8♣/1,1=J♠/1,2=3♥").unwrap();
+        let plan = board
+            .calculate(CardToPlay {
+                i: 1,
+                j: 1,
+                card: card!("J♥"),
+                target_field_for_king_ability: None,
+            })
+            .unwrap();
+        assert!(plan.combo);
+        assert_eq!(Vec::from_iter(plan.flipped()), vec![(0, 1), (1, 2)]);
+        assert!(plan.won().is_empty());
+        assert_eq!(plan.new_card_coordinates(), (1, 1));
+
+        let plan = board
+            .calculate(CardToPlay {
+                i: 1,
+                j: 1,
+                card: card!("K♠"),
+                target_field_for_king_ability: Some((0, 0)),
+            })
+            .unwrap();
+        assert_eq!(Vec::from_iter(plan.flipped()), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn try_new_rejects_invalid_boards() {
+        let field = |i, j, top_card: &str, hidden_cards: &[&str]| Field {
+            i,
+            j,
+            top_card: (!top_card.is_empty()).then(|| top_card.parse().unwrap()),
+            hidden_cards: hidden_cards.iter().map(|c| c.parse().unwrap()).collect(),
+        };
+        assert_eq!(Board::try_new(&[]).unwrap_err(), InvalidBoard::NoFields);
+        assert_eq!(
+            Board::try_new(&[field(0, 0, "2♦", &[]), field(4, 0, "3♦", &[])]).unwrap_err(),
+            InvalidBoard::TooLarge
+        );
+        assert_eq!(
+            Board::try_new(&[field(-128, 0, "2♦", &[]), field(127, 0, "3♦", &[])]).unwrap_err(),
+            InvalidBoard::TooLarge
+        );
+        assert_eq!(
+            Board::try_new(&[field(0, 0, "2♦", &[]), field(0, 0, "3♦", &[])]).unwrap_err(),
+            InvalidBoard::DuplicateCoordinates { i: 0, j: 0 }
+        );
+        assert_eq!(
+            Board::try_new(&[field(0, 0, "2♦", &[]), field(0, 1, "", &[])]).unwrap_err(),
+            InvalidBoard::EmptyField { i: 0, j: 1 }
+        );
+        assert_eq!(
+            Board::try_new(&[field(0, 0, "2♦", &[]), field(0, 1, "", &["2♦"])]).unwrap_err(),
+            InvalidBoard::DuplicateCard {
+                card: card!("2♦")
+            }
+        );
+        assert_eq!(
+            Board::try_new(&[field(53, 0, "2♦", &[])]).unwrap_err(),
+            InvalidBoard::CoordinatesOutOfRange { i: 53, j: 0 }
+        );
+        assert!(Board::try_new(&[field(0, 0, "2♦", &[]), field(3, 3, "", &["3♦"])]).is_ok());
+    }
+
+    // A board far away from (0, 0), e.g. from an adversarial request, used to cause panics in
+    // the bitboard code when playing next to it.
+    #[test]
+    fn cards_cannot_be_played_beyond_max_coordinate() {
+        let board = Board::from_position_string("52,-52=7♥").unwrap();
+        let area = board.playable_area();
+        assert_eq!((area.i_max, area.j_min), (52, -52));
+        assert_eq!(board.locations_for_card(card!("7♦")).num_entries(), 16);
+        let ctp = |i, j| CardToPlay {
+            card: card!("8♥"),
+            i,
+            j,
+            target_field_for_king_ability: None,
+        };
+        assert_eq!(
+            board.calculate(ctp(53, -52)).err(),
+            Some(IllegalCardPlayed::OutOfBounds)
+        );
+        let board = board.play_card(ctp(52, -51)).unwrap();
+        assert_eq!(board.threats().iter().count(), 0);
+    }
+    #[test]
+    fn small_board_with_lines_of_three() {
+        let rules = RuleSet::new(3, 3, true).unwrap();
+        let fields_list = vec![
+            (0, 0, CompactField::new().place_card(card!("2♦"))),
+            (1, 1, CompactField::new().place_card(card!("3♦"))),
+            (0, 1, CompactField::new().place_card(card!("4♦"))),
+        ];
+        assert_eq!(
+            Board::try_from_fields_list_with_rules(
+                vec![
+                    fields_list[0],
+                    (0, 3, CompactField::new().place_card(card!("3♦")))
+                ],
+                rules
+            )
+            .unwrap_err(),
+            InvalidBoard::TooLarge
+        );
+        let board = Board::try_from_fields_list_with_rules(fields_list, rules).unwrap();
+        assert_eq!(board.playable_area().size_i(), 4);
+        assert!(!board.is_in_bounds(0, 3));
+
+        let ctp = CardToPlay {
+            card: card!("5♦"),
+            i: 2,
+            j: 2,
+            target_field_for_king_ability: None,
+        };
+        let effects = board.calculate(ctp).unwrap();
+        assert_eq!(
+            effects.cards_won,
+            CardsSet::from_iter([card!("2♦"), card!("3♦")])
+        );
+        assert_eq!(effects.execute().rules(), rules);
+
+        // With the standard rules, three cards in a row are not won
+        let standard_board = Board::from_fields_list(board.to_vec());
+        assert!(standard_board.calculate(ctp).unwrap().cards_won.is_empty());
+    }
+
+    #[test]
+    fn full_small_board() {
+        let rules = RuleSet::new(2, 2, true).unwrap();
+        let fields_list = vec![
+            (0, 0, CompactField::new().place_card(card!("2♦"))),
+            (0, 1, CompactField::new().place_card(card!("3♥"))),
+            (1, 0, CompactField::new().place_card(card!("4♠"))),
+            (1, 1, CompactField::new().place_card(card!("5♣"))),
+        ];
+        let board = Board::try_from_fields_list_with_rules(fields_list, rules).unwrap();
+        assert!(!board.possible_to_play_card(card!("6♦")));
+        assert!(board.possible_to_play_card(card!("2♥")));
+    }
+    #[cfg(feature = "engine-serde")]
+    quickcheck! {
+        fn serde_roundtrip(input: TurnInput) -> bool {
+            let board = Board::new(&input.fields);
+            let json = serde_json::to_string(&board).unwrap();
+            let bytes = bincode::serialize(&board).unwrap();
+            let from_json: Board = serde_json::from_str(&json).unwrap();
+            let from_bytes: Board = bincode::deserialize(&bytes).unwrap();
+            let hearts_json = serde_json::to_string(&board.hearts()).unwrap();
+            let hearts_bytes = bincode::serialize(&board.hearts()).unwrap();
+            from_json == board
+                && from_bytes == board
+                && serde_json::from_str::<BitBoard>(&hearts_json).unwrap() == board.hearts()
+                && bincode::deserialize::<BitBoard>(&hearts_bytes).unwrap() == board.hearts()
+        }
+    }
+
+    #[cfg(feature = "engine-serde")]
+    #[test]
+    fn serde_formats() {
+        let board = Board::from_position_string("0,1=_(2♣Q♠)/0,0=7♥").unwrap();
+        assert_eq!(
+            serde_json::to_value(&board).unwrap(),
+            serde_json::json!({
+                "fields": [
+                    {"i": 0, "j": 0, "top_card": {"suit": "♥", "rank": "7"}, "hidden_cards": []},
+                    {"i": 0, "j": 1, "top_card": null, "hidden_cards": [
+                        {"suit": "♠", "rank": "Q"}, {"suit": "♣", "rank": "2"}
+                    ]},
+                ],
+                "rules": {"board_size": 4, "line_length": 4, "diagonals": true},
+            })
+        );
+        let field_json = r#"{"top_card": {"suit": "♥", "rank": "7"}, "hidden_cards": []}"#;
+        assert_eq!(
+            serde_json::from_str::<CompactField>(field_json).unwrap(),
+            board.get(0, 0).unwrap()
+        );
+
+        // Invalid values are rejected
+        let duplicate_card = r#"{"top_card": {"suit": "♥", "rank": "7"}, "hidden_cards": [{"suit": "♥", "rank": "7"}]}"#;
+        assert!(serde_json::from_str::<CompactField>(duplicate_card).is_err());
+        assert!(bincode::deserialize::<CardsSet>(&u64::MAX.to_le_bytes()).is_err());
+        assert!(bincode::deserialize::<CompactField>(&u64::MAX.to_le_bytes()).is_err());
+        assert!(bincode::deserialize::<BitBoard>(&u64::MAX.to_le_bytes()).is_err());
+        let too_large = r#"{"fields": [
+            {"i": 0, "j": 0, "top_card": {"suit": "♥", "rank": "7"}, "hidden_cards": []},
+            {"i": 0, "j": 4, "top_card": {"suit": "♥", "rank": "8"}, "hidden_cards": []}
+        ]}"#;
+        assert!(serde_json::from_str::<Board>(too_large).is_err());
+    }
 }
@@ -4,6 +4,7 @@
 /// half-open range, it's possible for a point with `i == i_max`
 /// to be contained in the area.
 #[cfg_attr(feature = "python", pyo3::pyclass(get_all, set_all))]
+#[cfg_attr(feature = "engine-serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug)]
 pub struct BoundingBox {
     pub i_min: i8,
@@ -3,6 +3,8 @@ use std::{
     iter::FusedIterator,
 };
 
+use crate::RuleSet;
+
 const I_SHIFT: u8 = 49 + 7;
 const J_SHIFT: u8 = 49;
 const BOARD_MASK: u64 = 0x1ffffffffffff;
@@ -127,7 +129,7 @@ impl BitBoard {
         } else {
             return false;
         };
-        if i_local >= 7 || j_local >= 7 {
+        if !(0..7).contains(&i_local) || !(0..7).contains(&j_local) {
             return false;
         }
         let idx = i_local * 7 + j_local;
@@ -249,6 +251,66 @@ impl BitBoard {
         }
     }
 
+    /// Like [`lines_going_through_point()`](Self::lines_going_through_point), but finds the
+    /// lines that are won according to the given rules.
+    ///
+    /// A line is a contiguous run of at least [`RuleSet::line_length()`] coordinates in the
+    /// set. Diagonal lines are only considered if [`RuleSet::diagonals()`] is set.
+    #[must_use]
+    pub fn lines_going_through_point_with_rules(
+        self,
+        point_i: i8,
+        point_j: i8,
+        rules: RuleSet,
+    ) -> BitBoard {
+        if rules == RuleSet::STANDARD {
+            return self.lines_going_through_point(point_i, point_j);
+        }
+        self.contiguous_lines_going_through_point(
+            point_i,
+            point_j,
+            rules.line_length(),
+            rules.diagonals(),
+        )
+    }
+
+    // The slow path for lines_going_through_point_with_rules(), which walks along every direction.
+    fn contiguous_lines_going_through_point(
+        self,
+        point_i: i8,
+        point_j: i8,
+        line_length: i8,
+        diagonals: bool,
+    ) -> BitBoard {
+        let mut result = Self {
+            bits: self.bits & OFFSET_MASK,
+        };
+        if !self.contains(point_i, point_j) {
+            return result;
+        }
+        let directions: &[(i8, i8)] = if diagonals {
+            &[(0, 1), (1, 0), (1, 1), (1, -1)]
+        } else {
+            &[(0, 1), (1, 0)]
+        };
+        for &(di, dj) in directions {
+            // The run consists of the points point + k * direction for k in k_min..=k_max
+            let (mut k_min, mut k_max) = (0, 0);
+            while self.contains(point_i + (k_min - 1) * di, point_j + (k_min - 1) * dj) {
+                k_min -= 1;
+            }
+            while self.contains(point_i + (k_max + 1) * di, point_j + (k_max + 1) * dj) {
+                k_max += 1;
+            }
+            if k_max - k_min + 1 >= line_length {
+                for k in k_min..=k_max {
+                    result = result.insert(point_i + k * di, point_j + k * dj);
+                }
+            }
+        }
+        result
+    }
+
     fn local_coords(self, i: i8, j: i8) -> (u8, u8) {
         let (offset_i, offset_j) = self.offset();
         debug_assert!(i >= offset_i);
@@ -522,6 +584,73 @@ impl ExactSizeIterator for BitBoardIter {
 
 impl FusedIterator for BitBoardIter {}
 
+// Human-readable formats get the center and a list of coordinates, binary formats the bits.
+#[cfg(feature = "engine-serde")]
+mod serialization {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct BitBoardRepr {
+        /// The center of the 7 x 7 area, see `empty_board_centered_at()`.
+        center: (i8, i8),
+        coordinates: Vec<(i8, i8)>,
+    }
+
+    impl Serialize for BitBoard {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                let (offset_i, offset_j) = self.offset();
+                BitBoardRepr {
+                    center: (offset_i + 3, offset_j + 3),
+                    coordinates: Vec::from_iter(*self),
+                }
+                .serialize(serializer)
+            } else {
+                serializer.serialize_u64(self.bits)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for BitBoard {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let is_valid_center =
+                |(i, j): (i8, i8)| (-52..=52).contains(&i) && (-52..=52).contains(&j);
+            if deserializer.is_human_readable() {
+                let repr = BitBoardRepr::deserialize(deserializer)?;
+                if !is_valid_center(repr.center) {
+                    return Err(D::Error::custom(
+                        "the center must be in the range [-52, 52]",
+                    ));
+                }
+                let (center_i, center_j) = repr.center;
+                let mut bitboard = BitBoard::empty_board_centered_at(repr.center);
+                for (i, j) in repr.coordinates {
+                    let distance = |a: i8, b: i8| (i16::from(a) - i16::from(b)).abs();
+                    if distance(i, center_i) > 3 || distance(j, center_j) > 3 {
+                        return Err(D::Error::custom(format!(
+                            "the coordinates ({}, {}) are outside of the 7 x 7 area",
+                            i, j
+                        )));
+                    }
+                    bitboard = bitboard.insert(i, j);
+                }
+                Ok(bitboard)
+            } else {
+                let bits = u64::deserialize(deserializer)?;
+                let (offset_i, offset_j) = decode_offset(bits);
+                if bits & !(OFFSET_MASK | BOARD_MASK) != 0
+                    || !is_valid_center((offset_i + 3, offset_j + 3))
+                {
+                    return Err(D::Error::custom("invalid bits in bitboard"));
+                }
+                Ok(Self { bits })
+            }
+        }
+    }
+}
+
 #[cfg(feature = "python")]
 mod python {
     use pyo3::pymethods;
@@ -617,6 +746,20 @@ mod tests {
             let j = j % 53;
             BitBoard::empty_board_centered_at((i, j)).offset() == (i - 3, j - 3)
         }
+
+        fn contiguous_lines_agree_with_patterns(bits: u16, point_idx: u8) -> bool {
+            // A subset of a 4 x 4 area, like the cards on a board with the standard rules
+            let mut bb = BitBoard::empty_board_centered_at((0, 0));
+            for idx in 0..16 {
+                if bits & (1 << idx) != 0 {
+                    bb = bb.insert(idx / 4, idx % 4);
+                }
+            }
+            let (point_i, point_j) = ((point_idx % 16 / 4) as i8, (point_idx % 4) as i8);
+            let bb = bb.insert(point_i, point_j);
+            bb.lines_going_through_point(point_i, point_j)
+                == bb.contiguous_lines_going_through_point(point_i, point_j, 4, true)
+        }
     }
 
     #[test]
@@ -648,6 +791,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detect_line_with_rules() {
+        let bb = BitBoard::empty_board_centered_at((0, 0))
+            .insert(0, 0)
+            .insert(1, 1)
+            .insert(2, 2)
+            .insert(0, 1)
+            .insert(0, 2);
+        let lines_of_3 = RuleSet::new(3, 3, true).unwrap();
+        assert_eq!(
+            bb.lines_going_through_point_with_rules(0, 0, lines_of_3),
+            bb
+        );
+        let no_diagonals = RuleSet::new(4, 3, false).unwrap();
+        assert_eq!(
+            Vec::from_iter(bb.lines_going_through_point_with_rules(0, 0, no_diagonals)),
+            vec![(0, 0), (0, 1), (0, 2)]
+        );
+        assert!(bb
+            .lines_going_through_point_with_rules(0, 0, RuleSet::STANDARD)
+            .is_empty());
+    }
+
     #[test]
     fn threes_in_a_row() {
         let bb_1 = BitBoard::empty_board_centered_at((-20, -10));
@@ -100,6 +100,25 @@ impl CompactField {
         }
     }
 
+    // INTERNAL - the bit representation, for compact encodings
+    pub(crate) fn bits(self) -> u64 {
+        self.bits
+    }
+
+    // INTERNAL - checks the layout of the bits, but not whether the top card is also among
+    // the hidden cards
+    pub(crate) fn from_bits(bits: u64) -> Option<Self> {
+        let top_card_idx = (bits & TOP_CARD_MASK) >> 52;
+        let has_top_card = bits & TOP_CARD_INDICATOR_BIT != 0;
+        let valid = bits & !(TOP_CARD_INDICATOR_BIT | TOP_CARD_MASK | HIDDEN_CARDS_MASK) == 0
+            && if has_top_card {
+                top_card_idx < 52
+            } else {
+                top_card_idx == 0
+            };
+        valid.then_some(Self { bits })
+    }
+
     pub fn into_field(self, i: i8, j: i8) -> Field {
         Field {
             i,
@@ -133,6 +152,61 @@ impl From<&Field> for CompactField {
     }
 }
 
+// Human-readable formats get the top card and a list of hidden cards, binary formats the bits.
+#[cfg(feature = "engine-serde")]
+mod serialization {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct CompactFieldRepr {
+        top_card: Option<Card>,
+        hidden_cards: CardsSet,
+    }
+
+    impl Serialize for CompactField {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                CompactFieldRepr {
+                    top_card: self.top_card(),
+                    hidden_cards: self.hidden_cards(),
+                }
+                .serialize(serializer)
+            } else {
+                serializer.serialize_u64(self.bits)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CompactField {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (top_card, hidden_cards) = if deserializer.is_human_readable() {
+                let repr = CompactFieldRepr::deserialize(deserializer)?;
+                (repr.top_card, repr.hidden_cards)
+            } else {
+                let bits = u64::deserialize(deserializer)?;
+                let field = CompactField::from_bits(bits)
+                    .ok_or_else(|| D::Error::custom("invalid bits in field"))?;
+                (field.top_card(), field.hidden_cards())
+            };
+            if let Some(card) = top_card.filter(|&card| hidden_cards.contains(card)) {
+                return Err(D::Error::custom(format!(
+                    "the card {} is both the top card and a hidden card",
+                    card
+                )));
+            }
+            let field = CompactField {
+                bits: hidden_cards.bits,
+            };
+            Ok(match top_card {
+                Some(card) => field.place_card(card),
+                None => field,
+            })
+        }
+    }
+}
+
 #[cfg(feature = "python")]
 mod python {
     use pyo3::pymethods;
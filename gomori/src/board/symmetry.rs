@@ -0,0 +1,342 @@
+use std::ops::Neg;
+
+use super::coordinate_in_range;
+use crate::{Board, CardToPlay, CompactField, MAX_COORDINATE};
+
+/// One of the eight symmetries of a square, i.e. a rotation or reflection.
+///
+/// The game rules are invariant under these symmetries: Applying the same symmetry to a
+/// board and a card to play gives the same outcome as playing the card on the original board.
+///
+/// Since this library doesn't assign a direction to the `i` and `j` coordinates, the variants
+/// are described by how they map a coordinate pair `(i, j)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /// `(i, j)` → `(i, j)`
+    Identity,
+    /// `(i, j)` → `(j, -i)`
+    Rotate90,
+    /// `(i, j)` → `(-i, -j)`
+    Rotate180,
+    /// `(i, j)` → `(-j, i)`
+    Rotate270,
+    /// `(i, j)` → `(-i, j)`
+    MirrorI,
+    /// `(i, j)` → `(i, -j)`
+    MirrorJ,
+    /// `(i, j)` → `(j, i)`
+    Transpose,
+    /// `(i, j)` → `(-j, -i)`
+    Antitranspose,
+}
+
+impl Symmetry {
+    /// All symmetries, starting with the identity.
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::MirrorI,
+        Symmetry::MirrorJ,
+        Symmetry::Transpose,
+        Symmetry::Antitranspose,
+    ];
+
+    /// Maps a coordinate pair.
+    ///
+    /// Returns `None` if a coordinate is larger than [`MAX_COORDINATE`] in absolute value.
+    pub fn apply(self, i: i8, j: i8) -> Option<(i8, i8)> {
+        if !coordinate_in_range(i) || !coordinate_in_range(j) {
+            return None;
+        }
+        Some(self.map(i, j))
+    }
+
+    fn map<T: Neg<Output = T>>(self, i: T, j: T) -> (T, T) {
+        match self {
+            Symmetry::Identity => (i, j),
+            Symmetry::Rotate90 => (j, -i),
+            Symmetry::Rotate180 => (-i, -j),
+            Symmetry::Rotate270 => (-j, i),
+            Symmetry::MirrorI => (-i, j),
+            Symmetry::MirrorJ => (i, -j),
+            Symmetry::Transpose => (j, i),
+            Symmetry::Antitranspose => (-j, -i),
+        }
+    }
+
+    /// The symmetry that undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+}
+
+/// A mapping between board coordinates: A [`Symmetry`] followed by a translation.
+///
+/// Returned by [`Board::canonicalize()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub symmetry: Symmetry,
+    /// Added to the first coordinate after applying the symmetry.
+    pub offset_i: i8,
+    /// Added to the second coordinate after applying the symmetry.
+    pub offset_j: i8,
+}
+
+impl Transform {
+    /// Maps a coordinate pair.
+    ///
+    /// Returns `None` if a coordinate is larger than [`MAX_COORDINATE`] in absolute value,
+    /// before or after the transform.
+    pub fn apply(self, i: i8, j: i8) -> Option<(i8, i8)> {
+        let (i, j) = self.symmetry.apply(i, j)?;
+        let translate = |coordinate: i8, offset: i8| {
+            coordinate
+                .checked_add(offset)
+                .filter(|&coordinate| coordinate_in_range(coordinate))
+        };
+        Some((translate(i, self.offset_i)?, translate(j, self.offset_j)?))
+    }
+
+    /// The transform that maps coordinates back.
+    pub fn inverse(self) -> Transform {
+        let symmetry = self.symmetry.inverse();
+        let (offset_i, offset_j) = symmetry.map(i16::from(self.offset_i), i16::from(self.offset_j));
+        // With a larger offset, no valid coordinate is mapped to a valid coordinate
+        let max_offset = 2 * i16::from(MAX_COORDINATE);
+        let negate = |offset: i16| (-offset).clamp(-max_offset, max_offset) as i8;
+        Transform {
+            symmetry,
+            offset_i: negate(offset_i),
+            offset_j: negate(offset_j),
+        }
+    }
+
+    /// Maps the coordinates of a card to play, including the target of a king's ability.
+    ///
+    /// To map a card to play on the transformed board back to the original board,
+    /// use the [`inverse()`](Self::inverse) transform.
+    ///
+    /// Returns `None` if one of the coordinates can't be mapped, see [`apply()`](Self::apply).
+    pub fn apply_to_card_to_play(self, card_to_play: CardToPlay) -> Option<CardToPlay> {
+        let (i, j) = self.apply(card_to_play.i, card_to_play.j)?;
+        let target_field_for_king_ability = match card_to_play.target_field_for_king_ability {
+            Some((tgt_i, tgt_j)) => Some(self.apply(tgt_i, tgt_j)?),
+            None => None,
+        };
+        Some(CardToPlay {
+            card: card_to_play.card,
+            i,
+            j,
+            target_field_for_king_ability,
+        })
+    }
+}
+
+impl Board {
+    /// Applies a transform to the coordinates of all fields.
+    ///
+    /// Returns `None` if a field would be moved beyond [`MAX_COORDINATE`].
+    pub fn transformed(&self, transform: Transform) -> Option<Board> {
+        let fields = self
+            .fields
+            .iter()
+            .map(|&(i, j, field)| {
+                let (i, j) = transform.apply(i, j)?;
+                Some((i, j, field))
+            })
+            .collect::<Option<_>>()?;
+        Some(
+            Board::try_from_fields_list_with_rules(fields, self.rules)
+                .expect("A transformed board is valid"),
+        )
+    }
+
+    /// Returns a normalized version of this board, plus the transform that was used to obtain it.
+    ///
+    /// All boards that are equal up to translation, rotation and reflection have the same
+    /// canonical board. The canonical board's bounding box starts at `(0, 0)`, and its
+    /// fields are sorted by `(i, j)`.
+    ///
+    /// Use [`Transform::apply_to_card_to_play()`] to map moves on this board to moves
+    /// on the canonical board, and the [inverse](Transform::inverse) transform for the other
+    /// direction.
+    pub fn canonicalize(&self) -> (Board, Transform) {
+        let mut best: Option<(Vec<_>, Transform)> = None;
+        for symmetry in Symmetry::ALL {
+            let mut fields: Vec<_> = self
+                .fields
+                .iter()
+                .map(|&(i, j, field)| {
+                    let (i, j) = symmetry.apply(i, j).expect("Board coordinates are valid");
+                    (i, j, field)
+                })
+                .collect();
+            let i_min = fields.iter().map(|&(i, _, _)| i).min().unwrap();
+            let j_min = fields.iter().map(|&(_, j, _)| j).min().unwrap();
+            for (i, j, _) in &mut fields {
+                *i -= i_min;
+                *j -= j_min;
+            }
+            fields.sort_by_key(|&(i, j, _)| (i, j));
+            let is_better = match &best {
+                None => true,
+                Some((best_fields, _)) => fields
+                    .iter()
+                    .map(sort_key)
+                    .lt(best_fields.iter().map(sort_key)),
+            };
+            if is_better {
+                let transform = Transform {
+                    symmetry,
+                    offset_i: -i_min,
+                    offset_j: -j_min,
+                };
+                best = Some((fields, transform));
+            }
+        }
+        let (fields, transform) = best.unwrap();
+        let board = Board::try_from_fields_list_with_rules(fields, self.rules)
+            .expect("A transformed board is valid");
+        (board, transform)
+    }
+}
+
+// An arbitrary but fixed order for fields
+fn sort_key(&(i, j, field): &(i8, i8, CompactField)) -> (i8, i8, Option<u8>, u64) {
+    (
+        i,
+        j,
+        field.top_card().map(|card| card.to_index()),
+        field.hidden_cards().bits,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::{arbitrary::PlayCardInput, card, Field};
+
+    quickcheck! {
+        fn canonical_board_is_invariant(input: PlayCardInput, symmetry_idx: u8, offset_i: i8, offset_j: i8) -> bool {
+            let board = Board::new(&input.fields);
+            let transform = Transform {
+                symmetry: Symmetry::ALL[usize::from(symmetry_idx % 8)],
+                offset_i: offset_i % 20,
+                offset_j: offset_j % 20,
+            };
+            let Some(transformed) = board.transformed(transform) else {
+                return true;
+            };
+            let (canonical_1, _) = board.canonicalize();
+            let (canonical_2, _) = transformed.canonicalize();
+            canonical_1 == canonical_2 && canonical_1.to_fields_vec() == canonical_2.to_fields_vec()
+        }
+
+        fn rules_are_invariant(input: PlayCardInput) -> bool {
+            let board = Board::new(&input.fields);
+            let (canonical, transform) = board.canonicalize();
+            let Some(ctp) = transform.apply_to_card_to_play(input.card_to_play) else {
+                return board.calculate(input.card_to_play).is_err();
+            };
+            match (board.calculate(input.card_to_play), canonical.calculate(ctp)) {
+                (Ok(effects), Ok(canonical_effects)) => {
+                    effects.cards_won == canonical_effects.cards_won
+                        && effects.combo == canonical_effects.combo
+                        && effects.execute().transformed(transform) == Some(canonical_effects.execute())
+                }
+                (Err(_), Err(_)) => true,
+                _ => false,
+            }
+        }
+
+        fn inverse_transform(i: i8, j: i8, symmetry_idx: u8, offset_i: i8, offset_j: i8) -> bool {
+            let (i, j) = (i % 53, j % 53);
+            let transform = Transform {
+                symmetry: Symmetry::ALL[usize::from(symmetry_idx % 8)],
+                offset_i: offset_i % 53,
+                offset_j: offset_j % 53,
+            };
+            match transform.apply(i, j) {
+                Some((i_t, j_t)) => transform.inverse().apply(i_t, j_t) == Some((i, j)),
+                None => true,
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_bbox_at_origin() {
+        let board = Board::new(&input_fields());
+        let (canonical, transform) = board.canonicalize();
+        assert_eq!((canonical.bbox().i_min, canonical.bbox().j_min), (0, 0));
+        assert_eq!(board.transformed(transform), Some(canonical));
+    }
+
+    #[test]
+    fn coordinates_out_of_range() {
+        assert_eq!(Symmetry::Rotate180.apply(i8::MIN, 0), None);
+        assert_eq!(
+            Symmetry::Rotate180.apply(-MAX_COORDINATE, 0),
+            Some((MAX_COORDINATE, 0))
+        );
+        let transform = Transform {
+            symmetry: Symmetry::Rotate90,
+            offset_i: i8::MAX,
+            offset_j: i8::MIN,
+        };
+        assert_eq!(transform.apply(MAX_COORDINATE, 0), None);
+        assert_eq!(transform.apply(-MAX_COORDINATE, -MAX_COORDINATE), None);
+        assert_eq!(transform.inverse().inverse().apply(0, 0), None);
+        let transform = Transform {
+            symmetry: Symmetry::Identity,
+            offset_i: MAX_COORDINATE,
+            offset_j: 0,
+        };
+        assert_eq!(transform.apply(0, 0), Some((MAX_COORDINATE, 0)));
+        assert_eq!(transform.apply(1, 0), None);
+        let king = CardToPlay {
+            card: card!("K♥"),
+            i: -1,
+            j: 0,
+            target_field_for_king_ability: Some((1, 0)),
+        };
+        assert!(transform.apply_to_card_to_play(king).is_none());
+        let king = CardToPlay {
+            target_field_for_king_ability: Some((-2, 0)),
+            ..king
+        };
+        let ctp = transform.apply_to_card_to_play(king).unwrap();
+        assert_eq!((ctp.i, ctp.j), (MAX_COORDINATE - 1, 0));
+        assert_eq!(
+            ctp.target_field_for_king_ability,
+            Some((MAX_COORDINATE - 2, 0))
+        );
+        assert_eq!(Board::new(&input_fields()).transformed(transform), None);
+    }
+
+    fn input_fields() -> Vec<Field> {
+        vec![
+            Field {
+                i: 5,
+                j: -3,
+                top_card: Some(card!("4♦")),
+                hidden_cards: BTreeSet::new(),
+            },
+            Field {
+                i: 6,
+                j: -1,
+                top_card: None,
+                hidden_cards: BTreeSet::from([card!("Q♠")]),
+            },
+        ]
+    }
+}
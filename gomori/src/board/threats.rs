@@ -0,0 +1,264 @@
+use crate::{BitBoard, Board, Card, CardToPlay, CardsSet, CompactField, Rank, Suit};
+
+/// A coordinate where a card of some suit would complete a line, returned by
+/// [`Board::threats()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Threat {
+    /// The suit of the line.
+    pub suit: Suit,
+    /// The first coordinate of the completing field.
+    pub i: i8,
+    /// The second coordinate of the completing field.
+    pub j: i8,
+    /// The fields that would be won, i.e. the line(s) without the completing field.
+    pub line: BitBoard,
+    /// The cards on the fields of [`line`](Self::line).
+    ///
+    /// These are the cards won by a card without an ability. A jack, queen or king that
+    /// covers the completing field may flip some of them.
+    pub cards_won: CardsSet,
+    /// The field that the card would be placed on, or `None` if the completing field is empty.
+    pub field: Option<CompactField>,
+    // Whether a line remains if the completing field is covered by a jack or queen
+    jack_completes: bool,
+    queen_completes: bool,
+    // A target for the king ability that doesn't break every line, if the completing field
+    // is covered by a king
+    king_target: Option<(i8, i8)>,
+}
+
+/// The [`Threat`]s on a board, grouped by suit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Threats {
+    by_suit: [Vec<Threat>; 4],
+}
+
+const SUITS: [Suit; 4] = [Suit::Diamond, Suit::Heart, Suit::Spade, Suit::Club];
+
+impl Board {
+    /// Finds all coordinates where a card of some suit would complete a line.
+    ///
+    /// Only the placement of a single card is considered, e.g. the coordinates where a combo
+    /// would first have to flip an opposing card are not threats.
+    pub fn threats(&self) -> Threats {
+        let mut threats = Threats::default();
+        let area = self.playable_area();
+        for suit in SUITS {
+            let cards_of_suit = self.bitboards[suit as usize];
+            // Every line needs at least one card of the suit besides the completing card
+            if cards_of_suit.num_entries() + 1 < self.rules.line_length() as u32 {
+                continue;
+            }
+            for i in area.i_min..=area.i_max {
+                for j in area.j_min..=area.j_max {
+                    let flipped = BitBoard::empty_board_centered_at(self.bitboards_center);
+                    let line = self.line_after_flipping(suit, i, j, flipped);
+                    if line.is_empty() {
+                        continue;
+                    }
+                    let field = self.get(i, j);
+                    let (mut jack_completes, mut queen_completes) = (true, true);
+                    let mut king_target = None;
+                    if field.is_some() {
+                        jack_completes = self.face_card_completes(suit, Rank::Jack, i, j);
+                        queen_completes = self.face_card_completes(suit, Rank::Queen, i, j);
+                        king_target = self.king_target(suit, i, j, line);
+                    }
+                    threats.by_suit[suit as usize].push(Threat {
+                        suit,
+                        i,
+                        j,
+                        line,
+                        cards_won: self.cards_on(line),
+                        field,
+                        jack_completes,
+                        queen_completes,
+                        king_target,
+                    });
+                }
+            }
+        }
+        threats
+    }
+
+    // The lines that a card of the given suit at (i, j) would complete, without (i, j).
+    fn line_after_flipping(&self, suit: Suit, i: i8, j: i8, flipped: BitBoard) -> BitBoard {
+        self.bitboards[suit as usize]
+            .insert(i, j)
+            .difference(flipped)
+            .lines_going_through_point_with_rules(i, j, self.rules)
+            .remove(i, j)
+    }
+
+    fn face_card_completes(&self, suit: Suit, rank: Rank, i: i8, j: i8) -> bool {
+        let card_to_play = CardToPlay {
+            card: Card { suit, rank },
+            i,
+            j,
+            target_field_for_king_ability: None,
+        };
+        let flipped = self
+            .fields_to_flip(card_to_play)
+            .expect("Jacks and queens don't need a target");
+        !self.line_after_flipping(suit, i, j, flipped).is_empty()
+    }
+
+    // Prefers a target that keeps the whole line intact.
+    fn king_target(&self, suit: Suit, i: i8, j: i8, line: BitBoard) -> Option<(i8, i8)> {
+        let mut best: Option<((i8, i8), u32)> = None;
+        for &(tgt_i, tgt_j, field) in &self.fields {
+            // Flipping the king itself would always break the line
+            if field.top_card().is_none() || (tgt_i, tgt_j) == (i, j) {
+                continue;
+            }
+            if !line.contains(tgt_i, tgt_j) {
+                return Some((tgt_i, tgt_j));
+            }
+            let flipped =
+                BitBoard::empty_board_centered_at(self.bitboards_center).insert(tgt_i, tgt_j);
+            let num_entries = self.line_after_flipping(suit, i, j, flipped).num_entries();
+            if num_entries > 0 && best.is_none_or(|(_, n)| num_entries > n) {
+                best = Some(((tgt_i, tgt_j), num_entries));
+            }
+        }
+        best.map(|(target, _)| target)
+    }
+}
+
+impl Threat {
+    /// Whether there is no card on the completing field yet.
+    pub fn completing_field_is_empty(&self) -> bool {
+        self.field.is_none()
+    }
+
+    /// Whether playing the given card on the completing field completes a line.
+    ///
+    /// This is the case if the card has the suit of the threat and can be placed on the
+    /// completing field, and its ability (if the field is covered) doesn't break the line.
+    pub fn can_be_completed_by(&self, card: Card) -> bool {
+        self.card_to_play(card).is_some()
+    }
+
+    /// The move that completes the line with the given card, if
+    /// [`can_be_completed_by()`](Self::can_be_completed_by) is true.
+    ///
+    /// For a king covering the completing field, a target for its ability is chosen that
+    /// preserves the line.
+    pub fn card_to_play(&self, card: Card) -> Option<CardToPlay> {
+        if card.suit != self.suit {
+            return None;
+        }
+        let mut card_to_play = CardToPlay {
+            card,
+            i: self.i,
+            j: self.j,
+            target_field_for_king_ability: None,
+        };
+        let Some(field) = self.field else {
+            return Some(card_to_play);
+        };
+        if !field.can_place_card(card) {
+            return None;
+        }
+        let completes = match card.rank {
+            Rank::Jack => self.jack_completes,
+            Rank::Queen => self.queen_completes,
+            Rank::King => {
+                card_to_play.target_field_for_king_ability = self.king_target;
+                self.king_target.is_some()
+            }
+            _ => true,
+        };
+        completes.then_some(card_to_play)
+    }
+}
+
+impl Threats {
+    /// The threats of the given suit.
+    pub fn for_suit(&self, suit: Suit) -> &[Threat] {
+        &self.by_suit[suit as usize]
+    }
+
+    /// All threats, ordered by suit.
+    pub fn iter(&self) -> impl Iterator<Item = &Threat> {
+        self.by_suit.iter().flatten()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_suit.iter().all(Vec::is_empty)
+    }
+
+    /// The threats that can be completed by the given card.
+    pub fn completable_by(&self, card: Card) -> impl Iterator<Item = &Threat> {
+        self.for_suit(card.suit)
+            .iter()
+            .filter(move |threat| threat.can_be_completed_by(card))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::{arbitrary::PlayCardInput, card};
+
+    quickcheck! {
+        fn threats_agree_with_calculate(input: PlayCardInput) -> bool {
+            let board = Board::new(&input.fields);
+            let card = input.card_to_play.card;
+            let threats = board.threats();
+            for threat in threats.iter() {
+                let cards_won = threat.card_to_play(card).map(|ctp| board.calculate(ctp).unwrap().cards_won);
+                if threat.can_be_completed_by(card) != cards_won.is_some_and(|won| !won.is_empty()) {
+                    return false;
+                }
+                if threat.line.num_entries() as usize != board.iter().filter(|&&(i, j, _)| threat.line.contains(i, j)).count() {
+                    return false;
+                }
+            }
+            // Every winning placement is found
+            board.placements_for_card(card).into_iter().all(|ctp| {
+                let wins = !board.calculate(ctp).unwrap().cards_won.is_empty();
+                !wins || threats.completable_by(card).any(|threat| (threat.i, threat.j) == (ctp.i, ctp.j))
+            })
+        }
+    }
+
+    #[test]
+    fn threats() {
+        let board =
+            Board::from_position_string("0,0=7♦/0,1=8♦/0,2=9♦/1,0=2♥/1,1=5♣/1,3=3♠").unwrap();
+        let threats = board.threats();
+        assert!(threats.for_suit(Suit::Heart).is_empty());
+        assert_eq!(threats.iter().count(), 1);
+
+        let diamonds = threats.for_suit(Suit::Diamond);
+        let covering = diamonds.iter().find(|t| (t.i, t.j) == (0, 3)).unwrap();
+        assert!(covering.completing_field_is_empty());
+        assert_eq!(covering.cards_won.len(), 3);
+        assert!(covering.can_be_completed_by(card!("A♦")));
+        assert!(!covering.can_be_completed_by(card!("A♥")));
+
+        let board = board
+            .play_card(covering.card_to_play(card!("3♦")).unwrap())
+            .unwrap();
+        assert!(board.threats().is_empty());
+
+        // Any card can cover the face-down field, but a jack would flip the 9♦
+        let board = Board::from_position_string("0,0=7♦/0,1=8♦/0,2=9♦/0,3=_(3♠)/1,0=5♣").unwrap();
+        let threat = board.threats().for_suit(Suit::Diamond)[0];
+        assert_eq!((threat.i, threat.j), (0, 3));
+        assert!(!threat.completing_field_is_empty());
+        assert!(threat.can_be_completed_by(card!("A♦")));
+        assert!(threat.can_be_completed_by(card!("4♦")));
+        assert!(!threat.can_be_completed_by(card!("J♦")));
+        assert!(threat.can_be_completed_by(card!("Q♦")));
+        assert_eq!(
+            threat
+                .card_to_play(card!("K♦"))
+                .and_then(|ctp| ctp.target_field_for_king_ability),
+            Some((1, 0))
+        );
+    }
+}
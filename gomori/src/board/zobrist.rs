@@ -0,0 +1,38 @@
+use crate::{Card, CompactField};
+
+// Zobrist hashing assigns a pseudo-random number to every "feature" of a position,
+// in our case every (coordinate, card, face-up/face-down) triple, and XORs together
+// the numbers for all features that are present. That makes it possible to update
+// the hash incrementally when a field changes.
+//
+// There are too many possible coordinates to store a table of random numbers, so
+// instead the numbers are computed on the fly by hashing the triple with SplitMix64.
+// The resulting keys are stable across program runs and platforms.
+
+/// The Zobrist key of a single field.
+pub(crate) fn field_key(i: i8, j: i8, field: CompactField) -> u64 {
+    let mut key = 0;
+    if let Some(card) = field.top_card() {
+        key ^= card_key(i, j, card, true);
+    }
+    for card in field.hidden_cards() {
+        key ^= card_key(i, j, card, false);
+    }
+    key
+}
+
+fn card_key(i: i8, j: i8, card: Card, face_up: bool) -> u64 {
+    let feature = u64::from(i as u8) << 16
+        | u64::from(j as u8) << 8
+        | u64::from(card.to_index()) << 1
+        | u64::from(face_up);
+    splitmix64(feature)
+}
+
+// See https://prng.di.unimi.it/splitmix64.c
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
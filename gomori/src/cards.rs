@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 /// A playing card in a standard 52-card game.
 #[cfg_attr(feature = "python", pyo3::pyclass(get_all, set_all))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
@@ -12,6 +13,7 @@ pub struct Card {
 
 /// The suit of a [card](Card).
 #[cfg_attr(feature = "python", pyo3::pyclass)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Suit {
@@ -27,6 +29,7 @@ pub enum Suit {
 
 /// The rank of a [card](Card).
 #[cfg_attr(feature = "python", pyo3::pyclass)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Rank {
@@ -128,7 +131,7 @@ impl Card {
 }
 
 /// The error type for the [`FromStr`] instance of [`Card`].
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CardFromStrErr {
     LessThanTwoChars,
     MoreThanTwoChars,
@@ -24,7 +24,7 @@ use crate::Card;
 /// new value instead of really mutating in-place (except for `std::ops::BitXxxAssign` trait methods).
 /// It is also [`Copy`], so a value is not consumed by methods with `self` receiver.
 #[cfg_attr(feature = "python", pyo3::pyclass)]
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct CardsSet {
     // Only the low 52 bits are used.
     pub(crate) bits: u64,
@@ -74,6 +74,11 @@ impl CardsSet {
             bits: self.bits & !(1u64 << card.to_index()),
         }
     }
+
+    // INTERNAL - the bitset, for compact encodings
+    pub(crate) fn from_bits(bits: u64) -> Option<Self> {
+        (bits & !VALID_BITS == 0).then_some(Self { bits })
+    }
 }
 
 impl std::ops::BitAnd for CardsSet {
@@ -191,6 +196,46 @@ impl ExactSizeIterator for CardsSetIter {
 
 impl FusedIterator for CardsSetIter {}
 
+// Human-readable formats get a list of cards, binary formats the bitset.
+#[cfg(feature = "engine-serde")]
+mod serialization {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::*;
+
+    impl Serialize for CardsSet {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if serializer.is_human_readable() {
+                serializer.collect_seq(*self)
+            } else {
+                serializer.serialize_u64(self.bits)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CardsSet {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if deserializer.is_human_readable() {
+                let mut set = CardsSet::new();
+                for card in Vec::<Card>::deserialize(deserializer)? {
+                    if set.contains(card) {
+                        return Err(D::Error::custom(format!(
+                            "the card {} appears more than once",
+                            card
+                        )));
+                    }
+                    set = set.insert(card);
+                }
+                Ok(set)
+            } else {
+                let bits = u64::deserialize(deserializer)?;
+                CardsSet::from_bits(bits)
+                    .ok_or_else(|| D::Error::custom("invalid bits in card set"))
+            }
+        }
+    }
+}
+
 #[cfg(feature = "python")]
 mod python {
     use pyo3::pymethods;
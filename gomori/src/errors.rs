@@ -29,6 +29,63 @@ impl std::fmt::Display for IllegalCardPlayed {
     }
 }
 
+/// The error type for [`Board::try_new()`](crate::Board::try_new), i.e. for invalid boards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidBoard {
+    NoFields,
+    TooLarge,
+    DuplicateCoordinates {
+        i: i8,
+        j: i8,
+    },
+    EmptyField {
+        i: i8,
+        j: i8,
+    },
+    DuplicateCard {
+        card: Card,
+    },
+    /// A coordinate is larger than [`MAX_COORDINATE`](crate::MAX_COORDINATE) in absolute value.
+    CoordinatesOutOfRange {
+        i: i8,
+        j: i8,
+    },
+}
+
+impl std::error::Error for InvalidBoard {}
+
+impl std::fmt::Display for InvalidBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidBoard::NoFields => write!(f, "The board has no fields"),
+            InvalidBoard::TooLarge => write!(f, "The board is larger than the board size"),
+            InvalidBoard::DuplicateCoordinates { i, j } => {
+                write!(
+                    f,
+                    "There is more than one field with the coordinates ({}, {})",
+                    i, j
+                )
+            }
+            InvalidBoard::EmptyField { i, j } => {
+                write!(f, "The field ({}, {}) has no cards on it", i, j)
+            }
+            InvalidBoard::DuplicateCard { card } => {
+                write!(f, "The card {} is on the board more than once", card)
+            }
+            InvalidBoard::CoordinatesOutOfRange { i, j } => {
+                write!(
+                    f,
+                    "The coordinates ({}, {}) are outside of the range from -{} to {}",
+                    i,
+                    j,
+                    crate::MAX_COORDINATE,
+                    crate::MAX_COORDINATE
+                )
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 /// The error type for one turn.
 pub enum IllegalMove {
@@ -100,6 +157,36 @@ impl std::fmt::Display for IllegalMove {
     }
 }
 
+/// The error type for [`BinaryMessage::decode()`](crate::BinaryMessage::decode).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    UnexpectedEnd,
+    TrailingBytes,
+    InvalidTag { what: &'static str, tag: u8 },
+    InvalidCard { index: u8 },
+    InvalidCardsSet { bits: u64 },
+    InvalidField { bits: u64 },
+    InvalidString,
+}
+
+impl std::error::Error for DecodeError {}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "The message ended unexpectedly"),
+            DecodeError::TrailingBytes => write!(f, "The message is longer than expected"),
+            DecodeError::InvalidTag { what, tag } => write!(f, "Invalid {} tag {}", what, tag),
+            DecodeError::InvalidCard { index } => write!(f, "Invalid card index {}", index),
+            DecodeError::InvalidCardsSet { bits } => {
+                write!(f, "Invalid card set bits {:#x}", bits)
+            }
+            DecodeError::InvalidField { bits } => write!(f, "Invalid field bits {:#x}", bits),
+            DecodeError::InvalidString => write!(f, "A string is not valid UTF-8"),
+        }
+    }
+}
+
 #[cfg(feature = "python")]
 mod python {
     use pyo3::create_exception;
@@ -120,6 +207,19 @@ mod python {
         }
     }
 
+    create_exception!(
+        gomori,
+        InvalidBoardException,
+        pyo3::exceptions::PyException,
+        "Describes why a board is invalid."
+    );
+
+    impl From<InvalidBoard> for PyErr {
+        fn from(err: InvalidBoard) -> PyErr {
+            InvalidBoardException::new_err(err.to_string())
+        }
+    }
+
     create_exception!(
         gomori,
         IllegalMoveException,
@@ -0,0 +1,256 @@
+use std::cmp::Ordering;
+
+use rand::rngs::StdRng;
+
+use crate::{
+    execute_first_turn_with_rules, execute_turn, Board, Card, Color, IllegalMove, PlayTurnResponse,
+    PlayerState, RuleSet, TurnOutcome,
+};
+
+/// The result of a finished [`Game`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    /// The player with the given index won more cards than the other player.
+    WonByPlayer { player_idx: usize },
+    /// Both players won the same number of cards.
+    Tie,
+}
+
+/// A complete game between two players, from dealing the cards to the final scoring.
+///
+/// This is the authoritative implementation of the game flow: It takes care of
+/// whose turn it is, ends the game when both players skip their turn in succession
+/// or when a player can't refill their hand, and determines the winner.
+///
+/// Players are identified by their index, `0` or `1`.
+///
+/// ```
+/// # use gomori::{Color, Game, PlayTurnResponse};
+/// # use rand::{rngs::StdRng, SeedableRng};
+/// let mut rng = StdRng::seed_from_u64(0);
+/// let mut game = Game::new([Color::Red, Color::Black], 0, &mut rng);
+/// let first_card = game.player_state(0).hand[0];
+/// game.apply_first_turn(first_card).unwrap();
+/// assert_eq!(game.current_player_idx(), 1);
+/// // Skipping a turn while a card could be played is rejected, and doesn't change the game.
+/// assert!(game.apply_turn(PlayTurnResponse(vec![])).is_err());
+/// assert_eq!(game.current_player_idx(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Game {
+    players: [PlayerState; 2],
+    /// `None` until the first turn has been played.
+    board: Option<Board>,
+    current_player_idx: usize,
+    /// Whether the previous turn was skipped.
+    turn_skipped: bool,
+    result: Option<GameResult>,
+    rules: RuleSet,
+}
+
+impl Game {
+    /// Deals the cards for a new game.
+    ///
+    /// `colors` are the colors of player 0 and player 1, and `starting_player_idx` is the
+    /// player who plays the first turn.
+    pub fn new(colors: [Color; 2], starting_player_idx: usize, rng: &mut StdRng) -> Self {
+        let [color_0, color_1] = colors;
+        Self::from_player_states(
+            [
+                PlayerState::new(color_0, rng),
+                PlayerState::new(color_1, rng),
+            ],
+            starting_player_idx,
+        )
+    }
+
+    /// Creates a new game with already dealt cards.
+    ///
+    /// Panics if `starting_player_idx` is not `0` or `1`.
+    pub fn from_player_states(players: [PlayerState; 2], starting_player_idx: usize) -> Self {
+        assert!(starting_player_idx < 2);
+        Self {
+            players,
+            board: None,
+            current_player_idx: starting_player_idx,
+            turn_skipped: false,
+            result: None,
+            rules: RuleSet::STANDARD,
+        }
+    }
+
+    /// Plays the game with a variant of the rules instead of the standard rules.
+    ///
+    /// Panics if the first turn has already been played.
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        assert!(self.board.is_none(), "The first turn was already played");
+        self.rules = rules;
+        self
+    }
+
+    /// The rules that the game is played with.
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// The player whose turn it is.
+    pub fn current_player_idx(&self) -> usize {
+        self.current_player_idx
+    }
+
+    /// The state of the player with the given index.
+    pub fn player_state(&self, player_idx: usize) -> &PlayerState {
+        &self.players[player_idx]
+    }
+
+    /// The board, or `None` if the first turn has not been played yet.
+    pub fn board(&self) -> Option<&Board> {
+        self.board.as_ref()
+    }
+
+    /// Plays the first turn for the current player.
+    ///
+    /// If the move is illegal, the game is left unchanged.
+    ///
+    /// Panics if the first turn has already been played.
+    pub fn apply_first_turn(&mut self, card: Card) -> Result<(), IllegalMove> {
+        assert!(self.board.is_none(), "The first turn was already played");
+        let mut state = self.players[self.current_player_idx].clone();
+        let board = execute_first_turn_with_rules(&mut state, card, self.rules)?;
+        self.players[self.current_player_idx] = state;
+        self.board = Some(board);
+        self.current_player_idx = 1 - self.current_player_idx;
+        Ok(())
+    }
+
+    /// Plays a regular turn for the current player.
+    ///
+    /// If the move is illegal, the game is left unchanged.
+    ///
+    /// Panics if the first turn has not been played yet, or if the game is over.
+    pub fn apply_turn(&mut self, action: PlayTurnResponse) -> Result<TurnOutcome, IllegalMove> {
+        assert!(!self.is_over(), "The game is already over");
+        let board = self
+            .board
+            .as_ref()
+            .expect("The first turn has not been played yet");
+        let mut state = self.players[self.current_player_idx].clone();
+        let mut board = board.clone();
+        let outcome = execute_turn(&mut state, &mut board, action)?;
+        self.players[self.current_player_idx] = state;
+        self.board = Some(board);
+
+        match outcome {
+            TurnOutcome::Normal { .. } => self.turn_skipped = false,
+            TurnOutcome::Skipped => {
+                if self.turn_skipped {
+                    // When both players couldn't play a card, the game ends
+                    self.finish();
+                } else {
+                    self.turn_skipped = true;
+                }
+            }
+            TurnOutcome::GameEnded => self.finish(),
+        }
+        self.current_player_idx = 1 - self.current_player_idx;
+        Ok(outcome)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// The result of the game, or `None` if it is not over yet.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    fn finish(&mut self) {
+        let num_cards_0 = self.players[0].cards_won.len();
+        let num_cards_1 = self.players[1].cards_won.len();
+        self.result = Some(match num_cards_0.cmp(&num_cards_1) {
+            Ordering::Less => GameResult::WonByPlayer { player_idx: 1 },
+            Ordering::Equal => GameResult::Tie,
+            Ordering::Greater => GameResult::WonByPlayer { player_idx: 0 },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::{CardToPlay, CardsSet};
+
+    // Plays the first possible card placement, like a very simple bot.
+    fn first_possible_turn(game: &Game) -> PlayTurnResponse {
+        let board = game.board().unwrap();
+        let hand = game.player_state(game.current_player_idx()).hand;
+        let mut board = board.clone();
+        let mut remaining = CardsSet::from_iter(hand);
+        let mut cards_to_play = vec![];
+        'outer: loop {
+            for card in remaining {
+                if let Some((i, j)) = board.locations_for_card(card).into_iter().next() {
+                    let ctp = CardToPlay {
+                        card,
+                        i,
+                        j,
+                        target_field_for_king_ability: Some((i, j)),
+                    };
+                    let effects = board.calculate(ctp).unwrap();
+                    let combo = effects.combo;
+                    board = effects.execute();
+                    cards_to_play.push(ctp);
+                    remaining = remaining.remove(card);
+                    if combo
+                        && remaining
+                            .into_iter()
+                            .any(|c| board.possible_to_play_card(c))
+                    {
+                        continue 'outer;
+                    }
+                    break 'outer;
+                }
+            }
+            break;
+        }
+        PlayTurnResponse(cards_to_play)
+    }
+
+    #[test]
+    fn play_full_game() {
+        let mut rng = StdRng::seed_from_u64(1234);
+        let mut game = Game::new([Color::Black, Color::Red], 1, &mut rng);
+        let card = game.player_state(1).hand[0];
+        game.apply_first_turn(card).unwrap();
+        let mut num_turns = 0;
+        while !game.is_over() {
+            let action = first_possible_turn(&game);
+            game.apply_turn(action).unwrap();
+            num_turns += 1;
+            assert!(num_turns < 100);
+        }
+        let num_cards = [
+            game.player_state(0).cards_won.len(),
+            game.player_state(1).cards_won.len(),
+        ];
+        let expected = match num_cards[0].cmp(&num_cards[1]) {
+            Ordering::Less => GameResult::WonByPlayer { player_idx: 1 },
+            Ordering::Equal => GameResult::Tie,
+            Ordering::Greater => GameResult::WonByPlayer { player_idx: 0 },
+        };
+        assert_eq!(game.result(), Some(expected));
+    }
+
+    #[test]
+    fn illegal_move_leaves_game_unchanged() {
+        let mut rng = StdRng::seed_from_u64(5678);
+        let mut game = Game::new([Color::Red, Color::Black], 0, &mut rng);
+        // A black card is never in the hand of the red player
+        assert!(game.apply_first_turn(crate::BLACK_CARDS[0]).is_err());
+        assert_eq!(game.current_player_idx(), 0);
+        assert!(game.board().is_none());
+    }
+}
@@ -0,0 +1,170 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{ArrayValidation, InstanceType, Metadata, RootSchema, Schema, SchemaObject};
+use schemars::{schema_for, JsonSchema};
+
+use crate::{Card, CardToPlay, HelloResponse, Okay, PlayTurnResponse, Request};
+
+/// JSON Schemas for the messages of the protocol, together with the names of their types.
+///
+/// [`Request`] describes everything that a bot receives. The responses are an [`Okay`], a
+/// [`Card`], a [`PlayTurnResponse`] or a [`HelloResponse`], depending on the request, and
+/// [`CardToPlay`] is included for convenience.
+///
+/// The `export_schemas` binary writes them to files.
+pub fn protocol_json_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("Request", schema_for!(Request)),
+        ("Okay", schema_for!(Okay)),
+        ("Card", schema_for!(Card)),
+        ("PlayTurnResponse", schema_for!(PlayTurnResponse)),
+        ("CardToPlay", schema_for!(CardToPlay)),
+        ("HelloResponse", schema_for!(HelloResponse)),
+    ]
+}
+
+// The derived schema of an empty tuple struct has an empty list of items, which is not valid
+impl JsonSchema for Okay {
+    fn schema_name() -> String {
+        String::from("Okay")
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(String::from("An acknowledgement without data.")),
+                ..Default::default()
+            })),
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                max_items: Some(0),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use jsonschema::JSONSchema;
+    use quickcheck::quickcheck;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::arbitrary::TurnInput;
+    use crate::{card, Color, GameOverReason, OpponentTurn};
+
+    fn schema(name: &str) -> JSONSchema {
+        let (_, schema) = protocol_json_schemas()
+            .into_iter()
+            .find(|(schema_name, _)| *schema_name == name)
+            .unwrap();
+        JSONSchema::compile(&serde_json::to_value(schema).unwrap()).unwrap()
+    }
+
+    fn matches_schema(name: &str, message: &impl serde::Serialize) -> bool {
+        schema(name).is_valid(&serde_json::to_value(message).unwrap())
+    }
+
+    quickcheck! {
+        fn schemas_match_serde(input: TurnInput, cards_to_play: Vec<CardToPlay>) -> bool {
+            let cards_won = BTreeSet::from_iter(input.fields.iter().filter_map(|f| f.top_card));
+            let requests = [
+                Request::PlayTurn {
+                    cards: input.hand,
+                    fields: input.fields.clone(),
+                    cards_won_by_opponent: cards_won.clone(),
+                    opponent_turn: Some(OpponentTurn::Played {
+                        cards_to_play: PlayTurnResponse(cards_to_play.clone()),
+                    }),
+                },
+                Request::PlayTurn {
+                    cards: input.hand,
+                    fields: input.fields.clone(),
+                    cards_won_by_opponent: BTreeSet::new(),
+                    opponent_turn: None,
+                },
+                Request::GameOver {
+                    winner: None,
+                    cards_won,
+                    cards_won_by_opponent: BTreeSet::new(),
+                    fields: input.fields,
+                    reason: GameOverReason::IllegalMove,
+                },
+            ];
+            requests.iter().all(|req| matches_schema("Request", req))
+                && cards_to_play.iter().all(|ctp| matches_schema("CardToPlay", ctp))
+                && matches_schema("PlayTurnResponse", &PlayTurnResponse(cards_to_play))
+                && input.hand.iter().all(|card| matches_schema("Card", card))
+        }
+    }
+
+    #[test]
+    fn schemas() {
+        let requests = [
+            Request::Hello {
+                protocol_version: 2,
+                features: vec![String::from("game_over")],
+            },
+            Request::NewGame { color: Color::Red },
+            Request::PlayFirstTurn {
+                cards: [
+                    card!("2♦"),
+                    card!("T♥"),
+                    card!("J♠"),
+                    card!("Q♣"),
+                    card!("A♦"),
+                ],
+            },
+            Request::PlayTurn {
+                cards: [
+                    card!("2♦"),
+                    card!("T♥"),
+                    card!("J♠"),
+                    card!("Q♣"),
+                    card!("A♦"),
+                ],
+                fields: vec![],
+                cards_won_by_opponent: BTreeSet::new(),
+                opponent_turn: Some(OpponentTurn::FirstTurn {
+                    card: card!("K♣")
+                }),
+            },
+            Request::Bye,
+        ];
+        for req in &requests {
+            assert!(matches_schema("Request", req), "{:?}", req);
+        }
+        assert!(matches_schema("Okay", &Okay()));
+        assert!(matches_schema(
+            "HelloResponse",
+            &HelloResponse {
+                name: String::from("MyBot"),
+                version: String::from("1.0"),
+                features: vec![],
+            }
+        ));
+
+        // What serde emits, and what it accepts
+        let card = schema("Card");
+        assert!(card.is_valid(&json!({"suit": "♦", "rank": "10"})));
+        assert!(!card.is_valid(&json!({"suit": "Diamond", "rank": "10"})));
+        assert!(!card.is_valid(&json!({"suit": "♦", "rank": "T"})));
+        let card_to_play = schema("CardToPlay");
+        let king = json!({"suit": "♥", "rank": "K"});
+        assert!(card_to_play.is_valid(&json!({"card": king, "i": 0, "j": -1})));
+        assert!(card_to_play.is_valid(
+            &json!({"card": king, "i": 0, "j": -1, "target_field_for_king_ability": [1, 2]})
+        ));
+        assert!(!card_to_play.is_valid(
+            &json!({"card": king, "i": 0, "j": -1, "target_field_for_king_ability": [1]})
+        ));
+        let request = schema("Request");
+        assert!(!request.is_valid(&json!({"type": "Shutdown"})));
+        assert!(!request.is_valid(&json!({"type": "NewGame", "color": "green"})));
+        assert!(!request.is_valid(&Value::Null));
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+use std::iter::FusedIterator;
+
+use crate::{Board, Card, CardToPlay, CardsSet, Undo};
+
+/// Returns an iterator over all legal turns that can be played with the given hand.
+///
+/// A turn is a sequence of [`CardToPlay`]s, as in a [`PlayTurnResponse`](crate::PlayTurnResponse).
+/// Every turn that is returned obeys the same rules as [`execute_turn()`](crate::execute_turn):
+/// - Every card is played at most once.
+/// - Only the last card may end the combo.
+/// - A combo is only ended prematurely when none of the remaining cards can be played.
+///
+/// If none of the cards can be played, the only legal turn is to skip, i.e. an empty turn.
+///
+/// Kings that are played on top of another card are returned with every possible
+/// target of their ability, see [`Board::placements_for_card()`].
+///
+/// ```
+/// # use gomori::{card, legal_turns, Board, CardsSet, Field};
+/// let board = Board::new(&[Field { i: 0, j: 0, top_card: Some(card!("7♥")), hidden_cards: Default::default() }]);
+/// let hand = CardsSet::from_iter([card!("7♦"), card!("2♦")]);
+/// for turn in legal_turns(&board, hand) {
+///     assert!(!turn.is_empty());
+/// }
+/// ```
+pub fn legal_turns(board: &Board, hand: CardsSet) -> LegalTurns {
+    let can_play = hand
+        .into_iter()
+        .any(|card| board.possible_to_play_card(card));
+    LegalTurns {
+        board: board.clone(),
+        hand,
+        frames: if can_play {
+            vec![Frame::new(board, hand)]
+        } else {
+            Vec::new()
+        },
+        played: Vec::new(),
+        skip_pending: !can_play,
+        seen_positions: None,
+    }
+}
+
+/// Iterator returned by [`legal_turns()`].
+pub struct LegalTurns {
+    /// The board after the cards in `played` have been made.
+    board: Board,
+    /// The cards that have not been played yet.
+    hand: CardsSet,
+    /// The placements at each depth of the search, one more than there are `played` cards.
+    frames: Vec<Frame>,
+    played: Vec<(CardToPlay, Undo)>,
+    /// Set if the only legal turn is to skip, and it hasn't been returned yet.
+    skip_pending: bool,
+    /// The positions, and remaining hands, reached by turns that were already returned.
+    seen_positions: Option<HashSet<(Board, CardsSet)>>,
+}
+
+struct Frame {
+    placements: Vec<CardToPlay>,
+    next_idx: usize,
+}
+
+impl Frame {
+    fn new(board: &Board, hand: CardsSet) -> Self {
+        Self {
+            placements: hand
+                .into_iter()
+                .flat_map(|card: Card| board.placements_for_card(card))
+                .collect(),
+            next_idx: 0,
+        }
+    }
+}
+
+impl LegalTurns {
+    /// Only return one turn for every resulting position.
+    ///
+    /// Two turns lead to the same position if they result in the same board, and play the same
+    /// set of cards. They also win the same cards, so in most cases it is enough to consider
+    /// only one of them.
+    pub fn deduplicate_positions(mut self) -> Self {
+        self.seen_positions = Some(HashSet::new());
+        self
+    }
+}
+
+impl Iterator for LegalTurns {
+    type Item = Vec<CardToPlay>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.skip_pending {
+            self.skip_pending = false;
+            return Some(Vec::new());
+        }
+        loop {
+            let frame = self.frames.last_mut()?;
+            let Some(&ctp) = frame.placements.get(frame.next_idx) else {
+                // All placements at this depth have been tried, go back up
+                self.frames.pop();
+                if let Some((ctp, undo)) = self.played.pop() {
+                    self.board.unmake(undo);
+                    self.hand = self.hand.insert(ctp.card);
+                }
+                continue;
+            };
+            frame.next_idx += 1;
+
+            let undo = self
+                .board
+                .make(&ctp)
+                .expect("Generated an illegal card placement");
+            let hand = self.hand.remove(ctp.card);
+            let must_continue = undo.combo
+                && hand
+                    .into_iter()
+                    .any(|c| self.board.possible_to_play_card(c));
+            if must_continue {
+                self.hand = hand;
+                self.frames.push(Frame::new(&self.board, hand));
+                self.played.push((ctp, undo));
+                continue;
+            }
+
+            let is_new_position = match &mut self.seen_positions {
+                Some(seen_positions) => seen_positions.insert((self.board.clone(), hand)),
+                None => true,
+            };
+            self.board.unmake(undo);
+            if is_new_position {
+                let mut turn: Vec<CardToPlay> = self.played.iter().map(|&(ctp, _)| ctp).collect();
+                turn.push(ctp);
+                return Some(turn);
+            }
+        }
+    }
+}
+
+impl FusedIterator for LegalTurns {}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::{quickcheck, TestResult};
+
+    use super::*;
+    use crate::arbitrary::TurnInput;
+    use crate::{card, execute_turn, Field, PlayTurnResponse, PlayerState};
+
+    // The number of turns grows very quickly with the number of combos,
+    // so only a prefix of the turns is checked.
+    const MAX_TURNS: usize = 500;
+
+    fn all_turns_are_legal(input: TurnInput) -> TestResult {
+        let board = Board::new(&input.fields);
+        let hand = CardsSet::from_iter(input.hand);
+        let turns: Vec<_> = legal_turns(&board, hand).take(MAX_TURNS).collect();
+        let num_unique_turns = turns
+            .iter()
+            .map(|turn| format!("{:?}", turn))
+            .collect::<HashSet<_>>()
+            .len();
+        if turns.is_empty() || num_unique_turns != turns.len() {
+            return TestResult::failed();
+        }
+        let mut positions = HashSet::new();
+        for turn in legal_turns(&board, hand)
+            .deduplicate_positions()
+            .take(MAX_TURNS)
+        {
+            match play(&board, input.hand, turn) {
+                Some(position) => {
+                    if !positions.insert(position) {
+                        return TestResult::failed();
+                    }
+                }
+                None => return TestResult::failed(),
+            }
+        }
+        if turns
+            .into_iter()
+            .any(|turn| play(&board, input.hand, turn).is_none())
+        {
+            return TestResult::failed();
+        }
+        TestResult::passed()
+    }
+
+    // Returns the resulting board and hand, or `None` if the turn is illegal.
+    fn play(board: &Board, hand: [Card; 5], turn: Vec<CardToPlay>) -> Option<(Board, CardsSet)> {
+        let mut state = PlayerState {
+            draw_pile: Vec::new(),
+            hand,
+            cards_won: CardsSet::new(),
+        };
+        let mut board = board.clone();
+        let cards_played = CardsSet::from_iter(turn.iter().map(|ctp| ctp.card));
+        execute_turn(&mut state, &mut board, PlayTurnResponse(turn)).ok()?;
+        Some((board, CardsSet::from_iter(hand) & !cards_played))
+    }
+
+    quickcheck! {
+        fn legal_turns_are_legal(input: TurnInput) -> TestResult {
+            all_turns_are_legal(input)
+        }
+    }
+
+    #[test]
+    fn every_single_card_turn_is_found() {
+        let board = Board::new(&[Field {
+            i: 0,
+            j: 0,
+            top_card: Some(card!("7♥")),
+            hidden_cards: Default::default(),
+        }]);
+        // The 7♦ can be played on the 7♥, but then the 2♣ must be played as well.
+        let hand = CardsSet::from_iter([card!("7♦"), card!("2♣")]);
+        let turns: Vec<_> = legal_turns(&board, hand).collect();
+        // Both cards can be played in the 48 free fields of the 7 x 7 playable area.
+        // Playing the 7♦ on the 7♥ is a combo, after which the 2♣ has 48 possible fields again.
+        assert_eq!(turns.len(), 48 + 48 + 48);
+        assert!(turns
+            .iter()
+            .all(|turn| turn.len() == 1 || (turn[0].card == card!("7♦") && turn.len() == 2)));
+    }
+}
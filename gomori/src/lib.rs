@@ -31,24 +31,57 @@
 //! called a "field" by this library, has consistent coordinates in every turn,
 //! no matter where the current 4x4 boundary is.
 //! As a result, these coordinates may be negative, or larger than 4. They are represented
-//! as an `i8`.
+//! as an `i8`, and their absolute value is at most [`MAX_COORDINATE`].
+//!
+//! # Feature flags
+//!
+//! - `python`: Python bindings, used by the `gomori-py` crate.
+//! - `engine-serde`: `Serialize` and `Deserialize` impls for [`Board`], [`CompactField`],
+//!   [`CardsSet`], [`BitBoard`], [`BoundingBox`] and [`RuleSet`]. Human-readable formats like
+//!   JSON get lists of cards and coordinates, while binary formats get the compact bit
+//!   representations. The protocol types always implement serde.
+//! - `json-schema`: [`protocol_json_schemas()`] for the protocol types, and the `export_schemas`
+//!   binary that writes them to files.
 
+pub use binary_protocol::*;
 pub use board::*;
 pub use cards::*;
 pub use cards_set::*;
 pub use errors::*;
+pub use game::*;
+#[cfg(feature = "json-schema")]
+pub use json_schema::*;
+pub use legal_turns::*;
+pub use notation::*;
+pub use perft::*;
 pub use player_state::*;
+pub use position::*;
 pub use protocol_types::*;
+pub use record::*;
+pub use rules::*;
 pub use turn::*;
 pub use visualization::*;
 
 #[cfg(test)]
 mod arbitrary;
+mod binary_protocol;
 mod board;
 mod cards;
 mod cards_set;
 mod errors;
+mod game;
+#[cfg(feature = "json-schema")]
+mod json_schema;
+mod legal_turns;
+mod notation;
+mod perft;
 mod player_state;
+mod position;
 mod protocol_types;
+mod record;
+#[cfg(test)]
+mod reference;
+mod rules;
+mod svg;
 mod turn;
 mod visualization;
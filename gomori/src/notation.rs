@@ -0,0 +1,207 @@
+// A compact textual notation for turns.
+//
+// A [`CardToPlay`] is written as the card (in the same format as for the
+// [`FromStr`] instance of [`Card`]), followed by `@` and its coordinates.
+// If it has a target for the king's ability, that follows after a `>`:
+//
+// - `7♦@-1,0` is the 7♦ played at `(-1, 0)`.
+// - `K♥@0,1>2,2` is the K♥ played at `(0, 1)`, flipping the card at `(2, 2)`.
+//
+// A [`PlayTurnResponse`] is a list of these separated by `;`, e.g. `K♥@0,1>2,2; 7♦@-1,0`.
+// A skipped turn is written as `-`.
+
+use std::str::FromStr;
+
+use crate::{Card, CardFromStrErr, CardToPlay, PlayTurnResponse, Rank, Suit};
+
+impl std::fmt::Display for CardToPlay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (rank_char, suit_char) = card_chars(self.card);
+        write!(f, "{}{}@{},{}", rank_char, suit_char, self.i, self.j)?;
+        if let Some((tgt_i, tgt_j)) = self.target_field_for_king_ability {
+            write!(f, ">{},{}", tgt_i, tgt_j)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PlayTurnResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "-");
+        }
+        for (idx, card_to_play) in self.0.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", card_to_play)?;
+        }
+        Ok(())
+    }
+}
+
+// The inverse of the FromStr instance of Card.
+pub(crate) fn card_chars(card: Card) -> (char, char) {
+    let rank_char = match card.rank {
+        Rank::Two => '2',
+        Rank::Three => '3',
+        Rank::Four => '4',
+        Rank::Five => '5',
+        Rank::Six => '6',
+        Rank::Seven => '7',
+        Rank::Eight => '8',
+        Rank::Nine => '9',
+        Rank::Ten => 'T',
+        Rank::Jack => 'J',
+        Rank::Queen => 'Q',
+        Rank::King => 'K',
+        Rank::Ace => 'A',
+    };
+    let suit_char = match card.suit {
+        Suit::Diamond => '♦',
+        Suit::Heart => '♥',
+        Suit::Spade => '♠',
+        Suit::Club => '♣',
+    };
+    (rank_char, suit_char)
+}
+
+/// The error type for the [`FromStr`] instance of [`CardToPlay`].
+#[derive(Clone, Copy, Debug)]
+pub enum CardToPlayFromStrErr {
+    /// There is no `@` after the card.
+    MissingCoordinates,
+    InvalidCard(CardFromStrErr),
+    /// The coordinates are not two comma-separated integers in the range of `i8`.
+    InvalidCoordinates,
+}
+
+impl std::error::Error for CardToPlayFromStrErr {}
+
+impl std::fmt::Display for CardToPlayFromStrErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CardToPlayFromStrErr::MissingCoordinates => {
+                write!(f, "Expected '@' followed by coordinates after the card")
+            }
+            CardToPlayFromStrErr::InvalidCard(err) => write!(f, "Invalid card: {:?}", err),
+            CardToPlayFromStrErr::InvalidCoordinates => {
+                write!(f, "Expected coordinates of the form 'i,j'")
+            }
+        }
+    }
+}
+
+impl FromStr for CardToPlay {
+    type Err = CardToPlayFromStrErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (card, coordinates) = s
+            .trim()
+            .split_once('@')
+            .ok_or(CardToPlayFromStrErr::MissingCoordinates)?;
+        let card = Card::from_str(card).map_err(CardToPlayFromStrErr::InvalidCard)?;
+        let (coordinates, target_field_for_king_ability) = match coordinates.split_once('>') {
+            Some((coordinates, target)) => (coordinates, Some(parse_coordinates(target)?)),
+            None => (coordinates, None),
+        };
+        let (i, j) = parse_coordinates(coordinates)?;
+        Ok(CardToPlay {
+            card,
+            i,
+            j,
+            target_field_for_king_ability,
+        })
+    }
+}
+
+fn parse_coordinates(s: &str) -> Result<(i8, i8), CardToPlayFromStrErr> {
+    let (i, j) = s
+        .split_once(',')
+        .ok_or(CardToPlayFromStrErr::InvalidCoordinates)?;
+    let i = i
+        .parse()
+        .map_err(|_| CardToPlayFromStrErr::InvalidCoordinates)?;
+    let j = j
+        .parse()
+        .map_err(|_| CardToPlayFromStrErr::InvalidCoordinates)?;
+    Ok((i, j))
+}
+
+/// The error type for the [`FromStr`] instance of [`PlayTurnResponse`].
+#[derive(Clone, Copy, Debug)]
+pub struct PlayTurnResponseFromStrErr {
+    /// The index of the card that could not be parsed.
+    pub card_idx: usize,
+    pub err: CardToPlayFromStrErr,
+}
+
+impl std::error::Error for PlayTurnResponseFromStrErr {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.err)
+    }
+}
+
+impl std::fmt::Display for PlayTurnResponseFromStrErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Error parsing card number {}: {}",
+            self.card_idx + 1,
+            self.err
+        )
+    }
+}
+
+impl FromStr for PlayTurnResponse {
+    type Err = PlayTurnResponseFromStrErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "-" {
+            return Ok(PlayTurnResponse(Vec::new()));
+        }
+        let cards_to_play = s
+            .split(';')
+            .enumerate()
+            .map(|(card_idx, ctp)| {
+                CardToPlay::from_str(ctp)
+                    .map_err(|err| PlayTurnResponseFromStrErr { card_idx, err })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(PlayTurnResponse(cards_to_play))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::card;
+
+    quickcheck! {
+        fn notation_roundtrip(cards_to_play: Vec<CardToPlay>) -> bool {
+            let turn = PlayTurnResponse(cards_to_play);
+            let parsed = PlayTurnResponse::from_str(&turn.to_string()).unwrap();
+            format!("{:?}", parsed) == format!("{:?}", turn)
+        }
+    }
+
+    #[test]
+    fn parse_turn() {
+        let turn = PlayTurnResponse::from_str("K♥@0,1>2,2;7♦@-1,0 ").unwrap();
+        assert_eq!(turn.0.len(), 2);
+        assert_eq!(turn.0[0].card, card!("K♥"));
+        assert_eq!((turn.0[0].i, turn.0[0].j), (0, 1));
+        assert_eq!(turn.0[0].target_field_for_king_ability, Some((2, 2)));
+        assert_eq!(turn.0[1].card, card!("7♦"));
+        assert_eq!((turn.0[1].i, turn.0[1].j), (-1, 0));
+        assert_eq!(turn.0[1].target_field_for_king_ability, None);
+        assert_eq!(turn.to_string(), "K♥@0,1>2,2; 7♦@-1,0");
+
+        assert!(PlayTurnResponse::from_str("-").unwrap().0.is_empty());
+        let err = PlayTurnResponse::from_str("2♣@0,0; 3♣@0").unwrap_err();
+        assert_eq!(err.card_idx, 1);
+        assert!(matches!(err.err, CardToPlayFromStrErr::InvalidCoordinates));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{Board, CardToPlay, CardsSet};
+
+/// Counts the sequences of `depth` card placements that can be made in a turn with the
+/// given hand.
+///
+/// This is mainly useful for testing and benchmarking the rules engine: Every card placement
+/// is found with [`Board::placements_for_card()`], so a king that is played on top of another
+/// card counts once for every target of its ability. A placement is only followed by another
+/// one if it is a combo. Unlike in [`legal_turns()`](crate::legal_turns), a turn may also end
+/// before it reaches `depth`, in which case it is not counted.
+///
+/// A `depth` of zero always gives 1.
+///
+/// ```
+/// # use gomori::{perft, Position};
+/// # use std::str::FromStr;
+/// let position = Position::from_str("0,0=7♥ 7♦2♦").unwrap();
+/// // The 7♦ can be played anywhere in the 7 x 7 playable area, the 2♦ not on the 7♥
+/// assert_eq!(perft(&position.board, position.hand.unwrap(), 1), 49 + 48);
+/// ```
+pub fn perft(board: &Board, hand: CardsSet, depth: u32) -> u64 {
+    let mut board = board.clone();
+    perft_recursive(&mut board, hand, depth)
+}
+
+/// Like [`perft()`], but returns the count for each placement of the first card separately.
+///
+/// This helps with finding the placements whose counts differ from the expected ones.
+pub fn perft_divide(board: &Board, hand: CardsSet, depth: u32) -> Vec<(CardToPlay, u64)> {
+    let mut board = board.clone();
+    let mut counts = Vec::new();
+    if depth == 0 {
+        return counts;
+    }
+    for card in hand {
+        for ctp in board.placements_for_card(card) {
+            let undo = board
+                .make(&ctp)
+                .expect("Generated an illegal card placement");
+            let count = if depth == 1 {
+                1
+            } else if undo.combo {
+                perft_recursive(&mut board, hand.remove(card), depth - 1)
+            } else {
+                0
+            };
+            board.unmake(undo);
+            counts.push((ctp, count));
+        }
+    }
+    counts
+}
+
+fn perft_recursive(board: &mut Board, hand: CardsSet, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut count = 0;
+    for card in hand {
+        let placements = board.placements_for_card(card);
+        if depth == 1 {
+            count += placements.len() as u64;
+            continue;
+        }
+        for ctp in placements {
+            let undo = board
+                .make(&ctp)
+                .expect("Generated an illegal card placement");
+            if undo.combo {
+                count += perft_recursive(board, hand.remove(card), depth - 1);
+            }
+            board.unmake(undo);
+        }
+    }
+    count
+}
+
+/// Reference positions for [`perft()`], with the expected counts.
+///
+/// Every entry is a [`Position`](crate::Position) string with a hand, and the counts for
+/// depths 1, 2, 3, and so on. These counts must not change unless the game rules change.
+pub const PERFT_SUITE: &[(&str, &[u64])] = &[
+    // The first turn
+    ("0,0=7♥ 7♦2♦K♥J♣A♠", &[243, 581, 723, 288, 0]),
+    // Many ways to win the diamonds
+    (
+        "0,0=7♦/0,1=8♦/0,2=9♦/1,0=2♥/1,1=5♣/1,3=3♠ K♥J♦Q♣A♦T♦",
+        &[106, 1375, 14595, 109684],
+    ),
+    // A full board, where every card is a combo
+    (
+        "0,0=2♦/0,1=3♥/0,2=4♠/0,3=5♣/1,0=6♥/1,1=7♠/1,2=8♣/1,3=9♦/2,0=T♠/2,1=J♣/2,2=Q♦/2,3=K♥/\
+         3,0=A♣/3,1=2♥/3,2=3♠/3,3=4♦ K♠Q♣J♦A♥5♦",
+        &[107, 5002, 132915],
+    ),
+    // Face-down fields and hidden cards
+    (
+        "0,0=_(2♣)/0,1=K♦(3♥)/1,1=Q♠/2,2=J♥ K♠Q♦A♥J♣5♣",
+        &[125, 1927, 17427, 86379],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::{arbitrary::TurnInput, Position};
+
+    // A straightforward version that creates a new board for every placement
+    fn perft_with_play_card(board: &Board, hand: CardsSet, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut count = 0;
+        for card in hand {
+            for ctp in board.placements_for_card(card) {
+                let effects = board.calculate(ctp).unwrap();
+                if depth == 1 {
+                    count += 1;
+                } else if effects.combo {
+                    count += perft_with_play_card(&effects.execute(), hand.remove(card), depth - 1);
+                }
+            }
+        }
+        count
+    }
+
+    quickcheck! {
+        fn perft_agrees_with_play_card(input: TurnInput) -> bool {
+            let board = Board::new(&input.fields);
+            let hand = CardsSet::from_iter(input.hand);
+            (0..=2).all(|depth| {
+                let count = perft(&board, hand, depth);
+                let divided: u64 = perft_divide(&board, hand, depth).iter().map(|&(_, n)| n).sum();
+                count == perft_with_play_card(&board, hand, depth) && (depth == 0 || count == divided)
+            })
+        }
+    }
+
+    #[test]
+    fn perft_suite() {
+        for &(position, expected_counts) in PERFT_SUITE {
+            let position = Position::from_str(position).unwrap();
+            let counts: Vec<u64> = (1..=expected_counts.len() as u32)
+                .map(|depth| perft(&position.board, position.hand.unwrap(), depth))
+                .collect();
+            assert_eq!(counts, expected_counts, "{}", position);
+        }
+    }
+}
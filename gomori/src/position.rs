@@ -0,0 +1,369 @@
+// A compact, FEN-like string format for positions.
+//
+// The board is written as a list of fields separated by `/`, sorted by `(i, j)`.
+// Every field is written as `i,j=` followed by the top card, or `_` if there is no
+// face-up card, and then the hidden cards in parentheses, if there are any.
+// Cards are written as for the `FromStr` instance of `Card`. For example:
+//
+//     0,0=7♥/0,1=_(2♣Q♠)/1,0=K♦(3♥)
+//
+// Boards with other rules than the standard ones start with the parameters of their rule set,
+// as in `[3,2,false]0,0=7♥`.
+//
+// A position may additionally contain the hand and any number of other card sets, separated
+// by spaces. A card set is written as a list of cards without separators, or `-` if it is empty.
+// If there are other card sets but no hand, the hand is written as `?`.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use crate::notation::card_chars;
+use crate::{Board, Card, CardFromStrErr, CardsSet, Field, InvalidBoard, InvalidRuleSet, RuleSet};
+
+/// A board, optionally together with the hand of the player to move and other sets of cards
+/// that are known to that player (e.g. the cards won by each player).
+///
+/// The [`Display`](std::fmt::Display) and [`FromStr`] instances use a compact string format,
+/// which extends the one of [`Board::to_position_string()`]:
+///
+/// ```
+/// # use gomori::{card, Position};
+/// # use std::str::FromStr;
+/// let position = Position::from_str("0,0=7♥/0,1=_(2♣Q♠) A♦3♥ - 4♠").unwrap();
+/// assert!(position.hand.unwrap().contains(card!("A♦")));
+/// assert!(position.card_sets[0].is_empty());
+/// assert_eq!(position.to_string(), "0,0=7♥/0,1=_(2♣Q♠) 3♥A♦ - 4♠");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub board: Board,
+    pub hand: Option<CardsSet>,
+    /// Other sets of cards, their meaning is up to the user.
+    pub card_sets: Vec<CardsSet>,
+}
+
+/// The error type for parsing a [`Position`] or a [`Board`] from a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionFromStrErr {
+    /// A field is not of the form `i,j=<top card>(<hidden cards>)`.
+    InvalidField {
+        field_idx: usize,
+    },
+    InvalidCard(CardFromStrErr),
+    /// A card appears twice in a set of cards.
+    DuplicateCard(Card),
+    InvalidBoard(InvalidBoard),
+    /// The rule set is not of the form `[<board size>,<line length>,<diagonals>]`.
+    InvalidRulesSyntax,
+    InvalidRuleSet(InvalidRuleSet),
+    /// The board contains spaces, so there is something else after it.
+    UnexpectedText,
+}
+
+impl std::error::Error for PositionFromStrErr {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PositionFromStrErr::InvalidBoard(err) => Some(err),
+            PositionFromStrErr::InvalidRuleSet(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PositionFromStrErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionFromStrErr::InvalidField { field_idx } => {
+                write!(f, "Field number {} is invalid", field_idx + 1)
+            }
+            PositionFromStrErr::InvalidCard(err) => write!(f, "Invalid card: {:?}", err),
+            PositionFromStrErr::DuplicateCard(card) => {
+                write!(f, "The card {} appears more than once", card)
+            }
+            PositionFromStrErr::InvalidBoard(err) => write!(f, "Invalid board: {}", err),
+            PositionFromStrErr::InvalidRulesSyntax => write!(
+                f,
+                "The rule set is not of the form [<board size>,<line length>,<diagonals>]"
+            ),
+            PositionFromStrErr::InvalidRuleSet(err) => write!(f, "Invalid rule set: {}", err),
+            PositionFromStrErr::UnexpectedText => write!(f, "Unexpected text after the board"),
+        }
+    }
+}
+
+impl Board {
+    /// Returns the board in a compact string format, e.g. `0,0=7♥/0,1=_(2♣Q♠)/1,0=K♦(3♥)`.
+    ///
+    /// Each field is written as its coordinates, followed by the top card (or `_` if the top
+    /// card is face-down) and the hidden cards in parentheses. The fields are sorted by `(i, j)`,
+    /// and the hidden cards are sorted as well, so equal boards have the same string.
+    ///
+    /// If the board does not use the [standard rules](RuleSet::STANDARD), the string starts
+    /// with the parameters of its [rules](Self::rules), e.g. `[3,2,false]0,0=7♥`.
+    pub fn to_position_string(&self) -> String {
+        let mut fields = self.to_vec();
+        fields.sort_by_key(|&(i, j, _)| (i, j));
+        let mut s = String::new();
+        let rules = self.rules();
+        if rules != RuleSet::STANDARD {
+            s.push_str(&format!(
+                "[{},{},{}]",
+                rules.board_size(),
+                rules.line_length(),
+                rules.diagonals()
+            ));
+        }
+        for (field_idx, (i, j, field)) in fields.into_iter().enumerate() {
+            if field_idx > 0 {
+                s.push('/');
+            }
+            s.push_str(&format!("{},{}=", i, j));
+            match field.top_card() {
+                Some(card) => push_card(&mut s, card),
+                None => s.push('_'),
+            }
+            if !field.hidden_cards().is_empty() {
+                s.push('(');
+                s.push_str(&card_set_string(field.hidden_cards()));
+                s.push(')');
+            }
+        }
+        s
+    }
+
+    /// Parses a board from the format of [`Board::to_position_string()`].
+    ///
+    /// This is guaranteed to give a board that is equal to the original one, including its
+    /// rules. Like
+    /// [`Board::try_new()`], this returns an error for invalid boards.
+    pub fn from_position_string(s: &str) -> Result<Board, PositionFromStrErr> {
+        if s.contains(char::is_whitespace) {
+            return Err(PositionFromStrErr::UnexpectedText);
+        }
+        let (rules, s) = match s.strip_prefix('[') {
+            Some(s) => {
+                let (rules, s) = s
+                    .split_once(']')
+                    .ok_or(PositionFromStrErr::InvalidRulesSyntax)?;
+                (parse_rules(rules)?, s)
+            }
+            None => (RuleSet::STANDARD, s),
+        };
+        let fields = if s.is_empty() {
+            Vec::new()
+        } else {
+            s.split('/')
+                .enumerate()
+                .map(|(field_idx, field_str)| {
+                    parse_field(field_str).ok_or(PositionFromStrErr::InvalidField { field_idx })?
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        Board::try_new_with_rules(&fields, rules).map_err(PositionFromStrErr::InvalidBoard)
+    }
+}
+
+fn parse_rules(s: &str) -> Result<RuleSet, PositionFromStrErr> {
+    let mut parts = s.split(',');
+    let mut next_part = || parts.next().ok_or(PositionFromStrErr::InvalidRulesSyntax);
+    let board_size = next_part()?.parse();
+    let line_length = next_part()?.parse();
+    let diagonals = next_part()?.parse();
+    match (board_size, line_length, diagonals, parts.next()) {
+        (Ok(board_size), Ok(line_length), Ok(diagonals), None) => {
+            RuleSet::new(board_size, line_length, diagonals)
+                .map_err(PositionFromStrErr::InvalidRuleSet)
+        }
+        _ => Err(PositionFromStrErr::InvalidRulesSyntax),
+    }
+}
+
+// Returns None for syntax errors, and an error for invalid cards.
+fn parse_field(s: &str) -> Option<Result<Field, PositionFromStrErr>> {
+    let (coordinates, cards) = s.split_once('=')?;
+    let (i, j) = coordinates.split_once(',')?;
+    let (i, j) = (i.parse().ok()?, j.parse().ok()?);
+    let (top_card, hidden_cards) = match cards.split_once('(') {
+        Some((top_card, hidden_cards)) => (top_card, hidden_cards.strip_suffix(')')?),
+        None => (cards, ""),
+    };
+    let top_card = match top_card {
+        "_" => None,
+        _ if top_card.chars().count() == 2 => match Card::from_str(top_card) {
+            Ok(card) => Some(card),
+            Err(err) => return Some(Err(PositionFromStrErr::InvalidCard(err))),
+        },
+        _ => return None,
+    };
+    let hidden_cards = match parse_cards(hidden_cards) {
+        Ok(cards) => cards,
+        Err(err) => return Some(Err(err)),
+    };
+    Some(Ok(Field {
+        i,
+        j,
+        top_card,
+        hidden_cards: BTreeSet::from_iter(hidden_cards),
+    }))
+}
+
+fn parse_cards(s: &str) -> Result<CardsSet, PositionFromStrErr> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut cards = CardsSet::new();
+    for card_chars in chars.chunks(2) {
+        let card = Card::from_str(&String::from_iter(card_chars))
+            .map_err(PositionFromStrErr::InvalidCard)?;
+        if cards.contains(card) {
+            return Err(PositionFromStrErr::DuplicateCard(card));
+        }
+        cards = cards.insert(card);
+    }
+    Ok(cards)
+}
+
+fn push_card(s: &mut String, card: Card) {
+    let (rank_char, suit_char) = card_chars(card);
+    s.push(rank_char);
+    s.push(suit_char);
+}
+
+fn card_set_string(cards: CardsSet) -> String {
+    if cards.is_empty() {
+        return String::from("-");
+    }
+    let mut s = String::new();
+    for card in cards {
+        push_card(&mut s, card);
+    }
+    s
+}
+
+fn parse_card_set(s: &str) -> Result<CardsSet, PositionFromStrErr> {
+    match s {
+        "-" => Ok(CardsSet::new()),
+        _ => parse_cards(s),
+    }
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.board.to_position_string())?;
+        match self.hand {
+            Some(hand) => write!(f, " {}", card_set_string(hand))?,
+            None if !self.card_sets.is_empty() => write!(f, " ?")?,
+            None => {}
+        }
+        for &cards in &self.card_sets {
+            write!(f, " {}", card_set_string(cards))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Position {
+    type Err = PositionFromStrErr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let board = Board::from_position_string(parts.next().unwrap_or(""))?;
+        let hand = match parts.next() {
+            Some("?") | None => None,
+            Some(hand) => Some(parse_card_set(hand)?),
+        };
+        let card_sets = parts.map(parse_card_set).collect::<Result<_, _>>()?;
+        Ok(Position {
+            board,
+            hand,
+            card_sets,
+        })
+    }
+}
+
+#[cfg(feature = "python")]
+mod python {
+    use pyo3::exceptions::PyValueError;
+    use pyo3::PyErr;
+
+    use super::*;
+
+    impl From<PositionFromStrErr> for PyErr {
+        fn from(err: PositionFromStrErr) -> PyErr {
+            PyValueError::new_err(err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::arbitrary::{ReachablePosition, TurnInput};
+    use crate::card;
+
+    quickcheck! {
+        fn position_string_roundtrip(input: TurnInput, card_sets: Vec<CardsSet>, has_hand: bool) -> bool {
+            let position = Position {
+                board: Board::new(&input.fields),
+                hand: has_hand.then(|| CardsSet::from_iter(input.hand)),
+                card_sets,
+            };
+            let s = position.to_string();
+            let parsed = Position::from_str(&s).unwrap();
+            parsed == position
+                && parsed.board.to_fields_vec() == position.board.to_fields_vec()
+                && parsed.to_string() == s
+        }
+
+        fn position_string_keeps_rules(input: ReachablePosition) -> bool {
+            let s = input.board.to_position_string();
+            let parsed = Board::from_position_string(&s).unwrap();
+            parsed == input.board && parsed.rules() == input.rules
+        }
+    }
+
+    #[test]
+    fn invalid_boards_are_rejected() {
+        use PositionFromStrErr::*;
+        let parse = Board::from_position_string;
+        assert_eq!(
+            parse("0,0=7♥/0,1").unwrap_err(),
+            InvalidField { field_idx: 1 }
+        );
+        assert_eq!(
+            parse("0,0=7♥(2♣").unwrap_err(),
+            InvalidField { field_idx: 0 }
+        );
+        assert_eq!(
+            parse("").unwrap_err(),
+            InvalidBoard(super::InvalidBoard::NoFields)
+        );
+        assert_eq!(
+            parse("0,0=7♥(2♣2♣)").unwrap_err(),
+            DuplicateCard(card!("2♣"))
+        );
+        assert_eq!(parse("0,0=7♥ 2♣").unwrap_err(), UnexpectedText);
+        assert!(matches!(parse("0,0=7X").unwrap_err(), InvalidCard(_)));
+        assert_eq!(parse("[3,2,true0,0=7♥").unwrap_err(), InvalidRulesSyntax);
+        assert_eq!(parse("[3,2]0,0=7♥").unwrap_err(), InvalidRulesSyntax);
+        assert_eq!(parse("[3,2,yes]0,0=7♥").unwrap_err(), InvalidRulesSyntax);
+        assert_eq!(
+            parse("[5,4,true]0,0=7♥").unwrap_err(),
+            InvalidRuleSet(super::InvalidRuleSet::BoardSizeOutOfRange)
+        );
+        assert_eq!(
+            parse("[3,3,true]0,0=7♥/0,3=2♣").unwrap_err(),
+            InvalidBoard(super::InvalidBoard::TooLarge)
+        );
+    }
+
+    #[test]
+    fn rules() {
+        let board = Board::from_position_string("[3,2,false]0,0=7♥/0,1=7♦").unwrap();
+        assert_eq!(board.rules(), RuleSet::new(3, 2, false).unwrap());
+        assert_eq!(board.to_position_string(), "[3,2,false]0,0=7♥/0,1=7♦");
+        let standard = Board::from_position_string("0,0=7♥/0,1=7♦").unwrap();
+        assert_ne!(board, standard);
+        assert_eq!(standard.to_position_string(), "0,0=7♥/0,1=7♦");
+    }
+}
@@ -4,10 +4,41 @@ use serde::{Deserialize, Serialize};
 
 use crate::Card;
 
+/// The version of the protocol that is announced in [`Request::Hello`].
+///
+/// Version 1 is the protocol without the `Hello` exchange, which is still used for bots that
+/// do not answer it.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Names of the optional protocol extensions that can be agreed on in [`Request::Hello`].
+pub mod features {
+    /// The bot receives a [`Request::GameOver`](super::Request::GameOver) after every game.
+    pub const GAME_OVER: &str = "game_over";
+    /// [`Request::PlayTurn`](super::Request::PlayTurn) includes the opponent's previous turn.
+    pub const OPPONENT_TURN: &str = "opponent_turn";
+    /// All messages after the `Hello` exchange use the encoding described in
+    /// [`BinaryMessage`](crate::BinaryMessage) instead of JSON.
+    pub const BINARY_ENCODING: &str = "binary_encoding";
+}
+
 /// Request for a bot to do something.
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Request {
+    /// The first request that a bot receives, before any game is started.
+    ///
+    /// The response should be a [`HelloResponse`]. A bot that does not answer it is
+    /// restarted and spoken to in protocol version 1, i.e. without any of the features.
+    Hello {
+        /// The protocol version spoken by the judge, currently [`PROTOCOL_VERSION`].
+        protocol_version: u32,
+        /// Optional protocol extensions that the judge offers.
+        ///
+        /// Bots must ignore features that they do not know.
+        #[serde(default)]
+        features: Vec<String>,
+    },
     /// Request to reset the bot's state for a new game.
     ///
     /// The response should be an [`Okay`].
@@ -34,11 +65,95 @@ pub enum Request {
         fields: Vec<Field>,
         /// The cards won by the opponent in the previous turn.
         cards_won_by_opponent: BTreeSet<Card>,
+        /// What the opponent did in the previous turn.
+        ///
+        /// Only sent to bots that accepted the [`features::OPPONENT_TURN`] feature.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        opponent_turn: Option<OpponentTurn>,
+    },
+    /// The game is over.
+    ///
+    /// Only sent to bots that accepted the [`features::GAME_OVER`] feature.
+    /// The response should be an [`Okay`].
+    GameOver {
+        /// The color of the winner, or `None` for a tie.
+        winner: Option<Color>,
+        /// All cards won by the bot in this game.
+        cards_won: BTreeSet<Card>,
+        /// All cards won by the opponent in this game.
+        cards_won_by_opponent: BTreeSet<Card>,
+        /// The final board, in the same format as in [`Request::PlayTurn`].
+        ///
+        /// Empty if the first turn was not played successfully.
+        fields: Vec<Field>,
+        /// Why the game ended.
+        reason: GameOverReason,
     },
     /// The bot should shut down.
     Bye,
 }
 
+/// The opponent's previous turn, as reported in [`Request::PlayTurn`].
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum OpponentTurn {
+    /// The opponent played the first turn of the game, placing the card at `(0, 0)`.
+    FirstTurn { card: Card },
+    /// The opponent played these cards.
+    Played { cards_to_play: PlayTurnResponse },
+    /// The opponent skipped their turn.
+    Skipped,
+}
+
+impl OpponentTurn {
+    /// The cards that were played, in order.
+    ///
+    /// The first turn is treated like a regular turn with a single card at `(0, 0)`, and a
+    /// skipped turn has no cards.
+    pub fn cards_to_play(&self) -> Vec<CardToPlay> {
+        match self {
+            OpponentTurn::FirstTurn { card } => vec![CardToPlay {
+                card: *card,
+                i: 0,
+                j: 0,
+                target_field_for_king_ability: None,
+            }],
+            OpponentTurn::Played { cards_to_play } => cards_to_play.0.clone(),
+            OpponentTurn::Skipped => vec![],
+        }
+    }
+}
+
+/// Why a game ended, as reported in [`Request::GameOver`].
+#[cfg_attr(feature = "python", pyo3::pyclass)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameOverReason {
+    /// Both players skipped their turn in succession.
+    BothPlayersSkipped,
+    /// A player could not refill their hand because their draw pile was empty.
+    DrawPileEmpty,
+    /// The loser made an illegal move.
+    IllegalMove,
+}
+
+/// The response to a [`Request::Hello`].
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HelloResponse {
+    /// The name of the bot.
+    pub name: String,
+    /// The version of the bot, in any format.
+    pub version: String,
+    /// The features offered by the judge that the bot wants to use.
+    ///
+    /// Features that were not offered are ignored.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
 /// Dummy struct for use in bot communication.
 ///
 /// Used to signal an acknowledgement without data.
@@ -47,6 +162,7 @@ pub struct Okay();
 
 /// Black or white.
 #[cfg_attr(feature = "python", pyo3::pyclass)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Color {
@@ -58,6 +174,7 @@ pub enum Color {
 
 /// A single field on the board, including coordinates.
 #[cfg_attr(feature = "python", pyo3::pyclass(get_all, set_all))]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Field {
     /// The first coordinate.
@@ -75,7 +192,12 @@ pub struct Field {
 /// Specifies which card to play, and where.
 ///
 /// Used in a [`PlayTurnResponse`], and in [`calculate()`](crate::Board::calculate).
+///
+/// The [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr) instances use a
+/// compact notation: `7♦@-1,0` is the 7♦ played at `(-1, 0)`, and `K♥@0,1>2,2` is the K♥
+/// played at `(0, 1)` with `(2, 2)` as the target of its ability.
 #[cfg_attr(feature = "python", pyo3::pyclass)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct CardToPlay {
     pub card: Card,
@@ -90,7 +212,12 @@ pub struct CardToPlay {
 }
 
 /// The cards to play in this turn, in order.
+///
+/// The [`Display`](std::fmt::Display) and [`FromStr`](std::str::FromStr) instances write
+/// the cards in the notation of [`CardToPlay`], separated by `;`, e.g. `K♥@0,1>2,2; 7♦@-1,0`.
+/// A skipped turn is written as `-`.
 #[cfg_attr(feature = "python", pyo3::pyclass)]
+#[cfg_attr(feature = "json-schema", derive(schemars::JsonSchema))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PlayTurnResponse(pub Vec<CardToPlay>);
 
@@ -117,6 +244,10 @@ mod python {
                 target_field_for_king_ability,
             }
         }
+
+        fn __str__(&self) -> String {
+            self.to_string()
+        }
     }
 
     #[pymethods]
@@ -125,5 +256,71 @@ mod python {
         fn py_new(cards_to_play: Vec<CardToPlay>) -> Self {
             Self(cards_to_play)
         }
+
+        fn __str__(&self) -> String {
+            self.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hello_wire_format() {
+        let req: Request = serde_json::from_str(
+            r#"{"type":"Hello","protocol_version":2,"features":["some_future_feature"]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            req,
+            Request::Hello { protocol_version: 2, features } if features == ["some_future_feature"]
+        ));
+        // The features may be left out on both sides
+        let req: Request =
+            serde_json::from_str(r#"{"type":"Hello","protocol_version":2}"#).unwrap();
+        assert!(matches!(req, Request::Hello { features, .. } if features.is_empty()));
+        let response: HelloResponse =
+            serde_json::from_str(r#"{"name":"MyBot","version":"1.0"}"#).unwrap();
+        assert!(response.features.is_empty());
+    }
+
+    #[test]
+    fn opponent_turn_is_optional() {
+        let play_turn = |opponent_turn| Request::PlayTurn {
+            cards: [Card::from_index(0); 5],
+            fields: vec![],
+            cards_won_by_opponent: BTreeSet::new(),
+            opponent_turn,
+        };
+        // Requests to bots that did not accept the feature are unchanged
+        let json = serde_json::to_string(&play_turn(None)).unwrap();
+        assert!(!json.contains("opponent_turn"));
+        let req: Request = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            req,
+            Request::PlayTurn {
+                opponent_turn: None,
+                ..
+            }
+        ));
+        let json = serde_json::to_string(&play_turn(Some(OpponentTurn::Skipped))).unwrap();
+        assert!(json.ends_with(r#""opponent_turn":{"type":"Skipped"}}"#));
+    }
+
+    #[test]
+    fn game_over_wire_format() {
+        let req = Request::GameOver {
+            winner: None,
+            cards_won: BTreeSet::new(),
+            cards_won_by_opponent: BTreeSet::new(),
+            fields: vec![],
+            reason: GameOverReason::BothPlayersSkipped,
+        };
+        assert_eq!(
+            serde_json::to_string(&req).unwrap(),
+            r#"{"type":"GameOver","winner":null,"cards_won":[],"cards_won_by_opponent":[],"fields":[],"reason":"both_players_skipped"}"#
+        );
     }
 }
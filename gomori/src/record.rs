@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Card, CardsSet, Color, Field, Game, GameResult, IllegalMove, PlayTurnResponse, PlayerState,
+};
+
+/// A complete record of a game, from which it can be [replayed](GameRecord::replay).
+///
+/// The record is self-contained: It includes the dealt cards, so the game can be replayed
+/// without knowing how they were shuffled.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// Player 0 and player 1.
+    pub players: [PlayerRecord; 2],
+    /// The seed of the random number generator that was used to set up the game, if any.
+    ///
+    /// This is purely informational, since the dealt cards are recorded in [`PlayerRecord`].
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub starting_player_idx: usize,
+    /// The card that the starting player played in the first turn.
+    pub first_turn: Card,
+    /// All turns after the first turn.
+    pub turns: Vec<TurnRecord>,
+    pub result: GameRecordResult,
+    /// The number of cards won by player 0 and player 1 at the end of the game.
+    pub scores: [u32; 2],
+}
+
+/// A player in a [`GameRecord`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerRecord {
+    pub name: String,
+    pub color: Color,
+    /// The hand that was dealt to the player.
+    pub hand: [Card; 5],
+    /// The draw pile after dealing the hand. Cards are drawn from the end.
+    pub draw_pile: Vec<Card>,
+}
+
+/// A turn in a [`GameRecord`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TurnRecord {
+    pub player_idx: usize,
+    pub action: PlayTurnResponse,
+    /// The board after the turn, or `None` if the turn was illegal.
+    ///
+    /// Only the last turn of a game can be illegal.
+    pub fields: Option<Vec<Field>>,
+}
+
+/// How a recorded game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GameRecordResult {
+    WonByPlayer {
+        player_idx: usize,
+    },
+    Tie,
+    /// The game was ended by an illegal move of the player with the given index.
+    IllegalMoveByPlayer {
+        player_idx: usize,
+    },
+}
+
+impl From<GameResult> for GameRecordResult {
+    fn from(result: GameResult) -> Self {
+        match result {
+            GameResult::WonByPlayer { player_idx } => GameRecordResult::WonByPlayer { player_idx },
+            GameResult::Tie => GameRecordResult::Tie,
+        }
+    }
+}
+
+/// The error type for [`GameRecord::replay()`].
+#[derive(Debug)]
+pub enum ReplayError {
+    /// A recorded player index is not `0` or `1`.
+    InvalidPlayerIdx,
+    /// The first turn was illegal, but the game doesn't end with an illegal move by the starting player.
+    IllegalFirstTurn(IllegalMove),
+    /// A turn was recorded for the player whose turn it isn't.
+    WrongPlayer { turn_idx: usize },
+    /// A turn was recorded after the game ended.
+    TurnAfterGameOver { turn_idx: usize },
+    /// A turn is illegal, but was recorded as legal.
+    IllegalTurn { turn_idx: usize, err: IllegalMove },
+    /// A turn is legal, but was recorded as illegal.
+    LegalTurnRecordedAsIllegal { turn_idx: usize },
+    /// The board after a turn differs from the recorded board.
+    BoardMismatch { turn_idx: usize },
+    /// The game ended differently than recorded, or not at all.
+    ResultMismatch { actual: Option<GameRecordResult> },
+    /// The scores at the end of the game differ from the recorded scores.
+    ScoresMismatch { actual: [u32; 2] },
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::IllegalFirstTurn(err) | ReplayError::IllegalTurn { err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::InvalidPlayerIdx => write!(f, "A player index is not 0 or 1"),
+            ReplayError::IllegalFirstTurn(_) => write!(f, "The first turn is illegal"),
+            ReplayError::WrongPlayer { turn_idx } => {
+                write!(f, "Turn {} was recorded for the wrong player", turn_idx)
+            }
+            ReplayError::TurnAfterGameOver { turn_idx } => {
+                write!(f, "Turn {} was recorded after the game ended", turn_idx)
+            }
+            ReplayError::IllegalTurn { turn_idx, .. } => write!(f, "Turn {} is illegal", turn_idx),
+            ReplayError::LegalTurnRecordedAsIllegal { turn_idx } => {
+                write!(f, "Turn {} is legal, but was recorded as illegal", turn_idx)
+            }
+            ReplayError::BoardMismatch { turn_idx } => {
+                write!(
+                    f,
+                    "The board after turn {} differs from the recording",
+                    turn_idx
+                )
+            }
+            ReplayError::ResultMismatch { actual } => {
+                write!(f, "The result {:?} differs from the recording", actual)
+            }
+            ReplayError::ScoresMismatch { actual } => {
+                write!(f, "The scores {:?} differ from the recording", actual)
+            }
+        }
+    }
+}
+
+impl GameRecord {
+    /// Re-executes the game with the rules engine, and checks that the recorded boards,
+    /// result and scores are correct.
+    ///
+    /// Returns the game in its final state.
+    pub fn replay(&self) -> Result<Game, ReplayError> {
+        if self.starting_player_idx > 1 || self.turns.iter().any(|turn| turn.player_idx > 1) {
+            return Err(ReplayError::InvalidPlayerIdx);
+        }
+        let [player_0, player_1] = &self.players;
+        let mut game = Game::from_player_states(
+            [player_0.initial_state(), player_1.initial_state()],
+            self.starting_player_idx,
+        );
+
+        let mut illegal_move_by = None;
+        if let Err(err) = game.apply_first_turn(self.first_turn) {
+            if !self.turns.is_empty() {
+                return Err(ReplayError::IllegalFirstTurn(err));
+            }
+            illegal_move_by = Some(self.starting_player_idx);
+        }
+
+        for (turn_idx, turn) in self.turns.iter().enumerate() {
+            if game.is_over() {
+                return Err(ReplayError::TurnAfterGameOver { turn_idx });
+            }
+            if turn.player_idx != game.current_player_idx() {
+                return Err(ReplayError::WrongPlayer { turn_idx });
+            }
+            match (game.apply_turn(turn.action.clone()), &turn.fields) {
+                (Ok(_), Some(fields)) => {
+                    if game.board().unwrap().to_fields_vec() != *fields {
+                        return Err(ReplayError::BoardMismatch { turn_idx });
+                    }
+                }
+                (Ok(_), None) => {
+                    return Err(ReplayError::LegalTurnRecordedAsIllegal { turn_idx });
+                }
+                (Err(_), None) if turn_idx + 1 == self.turns.len() => {
+                    illegal_move_by = Some(turn.player_idx);
+                }
+                (Err(err), _) => return Err(ReplayError::IllegalTurn { turn_idx, err }),
+            }
+        }
+
+        let actual = match illegal_move_by {
+            Some(player_idx) => Some(GameRecordResult::IllegalMoveByPlayer { player_idx }),
+            None => game.result().map(GameRecordResult::from),
+        };
+        if actual != Some(self.result) {
+            return Err(ReplayError::ResultMismatch { actual });
+        }
+        let scores = [
+            game.player_state(0).cards_won.len(),
+            game.player_state(1).cards_won.len(),
+        ];
+        if scores != self.scores {
+            return Err(ReplayError::ScoresMismatch { actual: scores });
+        }
+        Ok(game)
+    }
+}
+
+impl PlayerRecord {
+    fn initial_state(&self) -> PlayerState {
+        PlayerState {
+            draw_pile: self.draw_pile.clone(),
+            hand: self.hand,
+            cards_won: CardsSet::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::legal_turns;
+
+    // Plays a game where both players always play the first legal turn
+    fn record_game(seed: u64) -> GameRecord {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let colors = [Color::Red, Color::Black];
+        let mut game = Game::new(colors, 0, &mut rng);
+        let players = [0, 1].map(|player_idx| {
+            let state = game.player_state(player_idx);
+            PlayerRecord {
+                name: format!("Player {}", player_idx),
+                color: colors[player_idx],
+                hand: state.hand,
+                draw_pile: state.draw_pile.clone(),
+            }
+        });
+        let first_turn = game.player_state(0).hand[0];
+        game.apply_first_turn(first_turn).unwrap();
+        let mut turns = Vec::new();
+        while !game.is_over() {
+            let player_idx = game.current_player_idx();
+            let hand = CardsSet::from_iter(game.player_state(player_idx).hand);
+            let action = PlayTurnResponse(legal_turns(game.board().unwrap(), hand).next().unwrap());
+            game.apply_turn(action.clone()).unwrap();
+            turns.push(TurnRecord {
+                player_idx,
+                action,
+                fields: Some(game.board().unwrap().to_fields_vec()),
+            });
+        }
+        GameRecord {
+            players,
+            seed: Some(seed),
+            starting_player_idx: 0,
+            first_turn,
+            turns,
+            result: game.result().unwrap().into(),
+            scores: [
+                game.player_state(0).cards_won.len(),
+                game.player_state(1).cards_won.len(),
+            ],
+        }
+    }
+
+    #[test]
+    fn replay_recorded_game() {
+        let record = record_game(42);
+        let json = serde_json::to_string(&record).unwrap();
+        let record: GameRecord = serde_json::from_str(&json).unwrap();
+        assert!(record.replay().unwrap().is_over());
+    }
+
+    #[test]
+    fn replay_detects_wrong_board() {
+        let mut record = record_game(43);
+        let fields = record.turns[3].fields.as_mut().unwrap();
+        fields[0].i += 1;
+        assert!(matches!(
+            record.replay(),
+            Err(ReplayError::BoardMismatch { turn_idx: 3 })
+        ));
+    }
+}
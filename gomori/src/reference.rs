@@ -0,0 +1,198 @@
+// A deliberately simple implementation of playing a card, for differential testing.
+//
+// The board is a map from coordinates to fields, and lines are found by walking the grid
+// cell by cell. Nothing in here uses bitboards or the `Board` type, so that bugs in their
+// optimizations show up as disagreements with this implementation.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{Card, CardToPlay, CardsSet, Field, IllegalCardPlayed, Rank, RuleSet};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Cell {
+    top_card: Option<Card>,
+    hidden_cards: BTreeSet<Card>,
+}
+
+/// The effects of playing a card, as computed by [`play_card()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReferenceEffects {
+    /// The board after playing the card, sorted by coordinates.
+    pub fields: Vec<Field>,
+    pub cards_won: CardsSet,
+    pub combo: bool,
+    /// Only the flipped coordinates that have a card on them.
+    pub flipped: BTreeSet<(i8, i8)>,
+    pub won: BTreeSet<(i8, i8)>,
+}
+
+pub fn play_card(
+    fields: &[Field],
+    card_to_play: CardToPlay,
+    rules: RuleSet,
+) -> Result<ReferenceEffects, IllegalCardPlayed> {
+    let CardToPlay { card, i, j, .. } = card_to_play;
+    let mut grid: BTreeMap<(i8, i8), Cell> = fields
+        .iter()
+        .map(|field| {
+            let cell = Cell {
+                top_card: field.top_card,
+                hidden_cards: field.hidden_cards.clone(),
+            };
+            ((field.i, field.j), cell)
+        })
+        .collect();
+
+    // The new card must fit into a square of the board size together with all other cards
+    let coordinates = grid.keys().copied().chain([(i, j)]);
+    let (i_values, j_values): (Vec<i16>, Vec<i16>) = coordinates
+        .map(|(i, j)| (i16::from(i), i16::from(j)))
+        .unzip();
+    let span = |values: &[i16]| values.iter().max().unwrap() - values.iter().min().unwrap() + 1;
+    let board_size = i16::from(rules.board_size());
+    if span(&i_values) > board_size || span(&j_values) > board_size {
+        return Err(IllegalCardPlayed::OutOfBounds);
+    }
+
+    let combo = grid.contains_key(&(i, j));
+    if let Some(existing_card) = grid.get(&(i, j)).and_then(|cell| cell.top_card) {
+        let same_rank = card.rank == existing_card.rank;
+        let face_card_on_same_suit = matches!(card.rank, Rank::Jack | Rank::Queen | Rank::King)
+            && card.suit == existing_card.suit;
+        if !same_rank && card.rank != Rank::Ace && !face_card_on_same_suit {
+            return Err(IllegalCardPlayed::IncompatibleCard { existing_card });
+        }
+    }
+
+    // Only a card that is played on top of another one activates its ability
+    let mut to_flip = Vec::new();
+    if combo {
+        match card.rank {
+            Rank::Jack => to_flip = vec![(i - 1, j), (i + 1, j), (i, j - 1), (i, j + 1)],
+            Rank::Queen => {
+                to_flip = vec![
+                    (i - 1, j - 1),
+                    (i - 1, j + 1),
+                    (i + 1, j - 1),
+                    (i + 1, j + 1),
+                ]
+            }
+            Rank::King => {
+                let (tgt_i, tgt_j) = card_to_play
+                    .target_field_for_king_ability
+                    .ok_or(IllegalCardPlayed::NoTargetForKingAbility)?;
+                let target = grid
+                    .get(&(tgt_i, tgt_j))
+                    .ok_or(IllegalCardPlayed::TargetForKingAbilityDoesNotExist { tgt_i, tgt_j })?;
+                if target.top_card.is_none() && (tgt_i, tgt_j) != (i, j) {
+                    return Err(IllegalCardPlayed::TargetForKingAbilityIsFaceDown { tgt_i, tgt_j });
+                }
+                to_flip = vec![(tgt_i, tgt_j)];
+            }
+            _ => {}
+        }
+    }
+
+    // Place the card, then flip
+    let cell = grid.entry((i, j)).or_default();
+    if let Some(previous_top_card) = cell.top_card.replace(card) {
+        cell.hidden_cards.insert(previous_top_card);
+    }
+    let mut flipped = BTreeSet::new();
+    for coordinates in to_flip {
+        if let Some(cell) = grid.get_mut(&coordinates) {
+            if let Some(top_card) = cell.top_card.take() {
+                cell.hidden_cards.insert(top_card);
+            }
+            flipped.insert(coordinates);
+        }
+    }
+
+    // Walk from the new card in both directions along every line
+    let has_suit = |grid: &BTreeMap<(i8, i8), Cell>, i: i8, j: i8| {
+        grid.get(&(i, j))
+            .and_then(|cell| cell.top_card)
+            .is_some_and(|top_card| top_card.suit == card.suit)
+    };
+    let mut directions = vec![(0, 1), (1, 0)];
+    if rules.diagonals() {
+        directions.extend([(1, 1), (1, -1)]);
+    }
+    let mut won = BTreeSet::new();
+    if has_suit(&grid, i, j) {
+        for (di, dj) in directions {
+            let mut line = vec![(i, j)];
+            for sign in [-1, 1] {
+                let (mut line_i, mut line_j) = (i + sign * di, j + sign * dj);
+                while has_suit(&grid, line_i, line_j) {
+                    line.push((line_i, line_j));
+                    (line_i, line_j) = (line_i + sign * di, line_j + sign * dj);
+                }
+            }
+            if line.len() >= rules.line_length() as usize {
+                won.extend(
+                    line.into_iter()
+                        .filter(|&coordinates| coordinates != (i, j)),
+                );
+            }
+        }
+    }
+
+    let mut cards_won = CardsSet::new();
+    for coordinates in &won {
+        let cell = grid.remove(coordinates).unwrap();
+        for card in cell.top_card.into_iter().chain(cell.hidden_cards) {
+            cards_won = cards_won.insert(card);
+        }
+    }
+
+    let fields = grid
+        .into_iter()
+        .map(|((i, j), cell)| Field {
+            i,
+            j,
+            top_card: cell.top_card,
+            hidden_cards: cell.hidden_cards,
+        })
+        .collect();
+    Ok(ReferenceEffects {
+        fields,
+        cards_won,
+        combo,
+        flipped,
+        won,
+    })
+}
+
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::arbitrary::ReachablePosition;
+
+    quickcheck! {
+        fn calculate_agrees_with_reference(input: ReachablePosition) -> bool {
+            let ReachablePosition { rules, board, card_to_play } = input;
+            let fields = board.to_fields_vec();
+            match (board.calculate(card_to_play), play_card(&fields, card_to_play, rules)) {
+                (Ok(effects), Ok(expected)) => {
+                    let same_effects = effects.cards_won == expected.cards_won
+                        && effects.combo == expected.combo
+                        && BTreeSet::from_iter(effects.flipped()) == expected.flipped
+                        && BTreeSet::from_iter(effects.won()) == expected.won;
+                    let after_execute = effects.execute();
+                    let mut after = after_execute.to_fields_vec();
+                    after.sort_by_key(|field| (field.i, field.j));
+                    // make() and unmake() must agree with execute()
+                    let mut made = board.clone();
+                    let undo = made.make(&card_to_play).unwrap();
+                    let same_made = made == after_execute;
+                    made.unmake(undo);
+                    same_effects && after == expected.fields && same_made && made == board
+                }
+                (Err(err), Err(expected_err)) => err == expected_err,
+                _ => false,
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+/// Parameters for variants of the game rules.
+///
+/// The default is [`RuleSet::STANDARD`], for which the library uses faster code paths.
+///
+/// # Limitations
+///
+/// [`BitBoard`](crate::BitBoard)s cover a 7 x 7 area, which is just large enough for the
+/// [playable area](crate::Board::playable_area) of a 4 x 4 board. Therefore the board size
+/// can be at most 4.
+#[cfg_attr(feature = "engine-serde", derive(serde::Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RuleSet {
+    board_size: i8,
+    line_length: i8,
+    diagonals: bool,
+}
+
+/// The error type for [`RuleSet::new()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidRuleSet {
+    /// The board size is not between 2 and 4.
+    BoardSizeOutOfRange,
+    /// The line length is not between 2 and the board size.
+    LineLengthOutOfRange,
+}
+
+impl std::error::Error for InvalidRuleSet {}
+
+impl std::fmt::Display for InvalidRuleSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidRuleSet::BoardSizeOutOfRange => {
+                write!(f, "The board size must be between 2 and 4")
+            }
+            InvalidRuleSet::LineLengthOutOfRange => {
+                write!(f, "The line length must be between 2 and the board size")
+            }
+        }
+    }
+}
+
+impl RuleSet {
+    /// The rules of the real game: A 4 x 4 board, where lines of 4 cards are won,
+    /// including diagonal ones.
+    pub const STANDARD: RuleSet = RuleSet {
+        board_size: 4,
+        line_length: 4,
+        diagonals: true,
+    };
+
+    /// Creates a rule set, see the getters for the meaning of the parameters.
+    pub fn new(board_size: i8, line_length: i8, diagonals: bool) -> Result<Self, InvalidRuleSet> {
+        if !(2..=4).contains(&board_size) {
+            return Err(InvalidRuleSet::BoardSizeOutOfRange);
+        }
+        if !(2..=board_size).contains(&line_length) {
+            return Err(InvalidRuleSet::LineLengthOutOfRange);
+        }
+        Ok(Self {
+            board_size,
+            line_length,
+            diagonals,
+        })
+    }
+
+    /// The cards on the board must fit into an area of `board_size` x `board_size`.
+    pub const fn board_size(self) -> i8 {
+        self.board_size
+    }
+
+    /// The minimum number of cards of the same suit in a row that are won.
+    pub const fn line_length(self) -> i8 {
+        self.line_length
+    }
+
+    /// Whether diagonal lines are won, in addition to horizontal and vertical ones.
+    pub const fn diagonals(self) -> bool {
+        self.diagonals
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+// Deserializing goes through RuleSet::new() to validate the parameters.
+#[cfg(feature = "engine-serde")]
+mod serialization {
+    use serde::{de::Error, Deserialize, Deserializer};
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct RuleSetRepr {
+        board_size: i8,
+        line_length: i8,
+        diagonals: bool,
+    }
+
+    impl<'de> Deserialize<'de> for RuleSet {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repr = RuleSetRepr::deserialize(deserializer)?;
+            RuleSet::new(repr.board_size, repr.line_length, repr.diagonals)
+                .map_err(D::Error::custom)
+        }
+    }
+}
@@ -0,0 +1,347 @@
+// Rendering of boards and turns as standalone SVG images.
+//
+// The `i` coordinate is drawn from top to bottom and the `j` coordinate from left to right,
+// like in `Board::render()`.
+
+use std::fmt::Write;
+
+use crate::notation::card_chars;
+use crate::{Board, BoundingBox, Card, IllegalMove, PlayTurnResponse, Suit};
+
+const CELL_SIZE: i32 = 56;
+const CARD_INSET: i32 = 4;
+// Space for the coordinate labels
+const MARGIN: i32 = 28;
+const TITLE_HEIGHT: i32 = 28;
+const PANEL_GAP: i32 = 40;
+
+const STYLE: &str = "text{font-family:sans-serif}\
+    .card{fill:#fff;stroke:#333;stroke-width:1.5}\
+    .face-down{fill:#4a6fa5;stroke:#333;stroke-width:1.5}\
+    .rank{font-size:20px;text-anchor:middle;dominant-baseline:central}\
+    .hidden{font-size:11px;text-anchor:end;fill:#555}\
+    .label{font-size:12px;text-anchor:middle;dominant-baseline:central;fill:#777}\
+    .title{font-size:14px;font-weight:bold}\
+    .placed{fill:none;stroke:#1f77b4;stroke-width:3}\
+    .won{fill:#f2c94c;fill-opacity:0.3;stroke:#d4a017;stroke-width:3;stroke-dasharray:6 4}\
+    .step{font-size:11px;fill:#fff;text-anchor:middle;dominant-baseline:central}\
+    .arrow{stroke:#d62728;stroke-width:2.5;marker-end:url(#arrowhead)}";
+
+// A card that was placed during a turn.
+struct Placement {
+    i: i8,
+    j: i8,
+    king_target: Option<(i8, i8)>,
+}
+
+// An image consisting of one or more panels that show a board each.
+struct SvgImage {
+    body: String,
+    area: BoundingBox,
+    with_titles: bool,
+}
+
+impl SvgImage {
+    fn new(area: BoundingBox, with_titles: bool) -> Self {
+        Self {
+            body: String::new(),
+            area,
+            with_titles,
+        }
+    }
+
+    fn panel_width(&self) -> i32 {
+        2 * MARGIN + CELL_SIZE * i32::from(self.area.size_j())
+    }
+
+    fn panel_height(&self) -> i32 {
+        let title_height = if self.with_titles { TITLE_HEIGHT } else { 0 };
+        title_height + 2 * MARGIN + CELL_SIZE * i32::from(self.area.size_i())
+    }
+
+    // The top left corner of a cell in the given panel.
+    fn cell_position(&self, panel_idx: i32, i: i8, j: i8) -> (i32, i32) {
+        let title_height = if self.with_titles { TITLE_HEIGHT } else { 0 };
+        let x = panel_idx * (self.panel_width() + PANEL_GAP)
+            + MARGIN
+            + CELL_SIZE * i32::from(j - self.area.j_min);
+        let y = title_height + MARGIN + CELL_SIZE * i32::from(i - self.area.i_min);
+        (x, y)
+    }
+
+    fn cell_center(&self, panel_idx: i32, i: i8, j: i8) -> (i32, i32) {
+        let (x, y) = self.cell_position(panel_idx, i, j);
+        (x + CELL_SIZE / 2, y + CELL_SIZE / 2)
+    }
+
+    fn draw_title(&mut self, panel_idx: i32, title: &str) {
+        let x = panel_idx * (self.panel_width() + PANEL_GAP) + MARGIN;
+        write!(
+            self.body,
+            r#"<text class="title" x="{}" y="{}">{}</text>"#,
+            x,
+            TITLE_HEIGHT - 8,
+            title
+        )
+        .unwrap();
+    }
+
+    fn draw_board(&mut self, panel_idx: i32, board: &Board) {
+        // Coordinate labels
+        for j in self.area.j_min..=self.area.j_max {
+            let (x, y) = self.cell_position(panel_idx, self.area.i_min, j);
+            let (label_x, label_y) = (x + CELL_SIZE / 2, y - MARGIN / 2);
+            write!(
+                self.body,
+                r#"<text class="label" x="{}" y="{}">{}</text>"#,
+                label_x, label_y, j
+            )
+            .unwrap();
+        }
+        for i in self.area.i_min..=self.area.i_max {
+            let (x, y) = self.cell_position(panel_idx, i, self.area.j_min);
+            let (label_x, label_y) = (x - MARGIN / 2, y + CELL_SIZE / 2);
+            write!(
+                self.body,
+                r#"<text class="label" x="{}" y="{}">{}</text>"#,
+                label_x, label_y, i
+            )
+            .unwrap();
+        }
+
+        for field in board.to_fields_vec() {
+            let (x, y) = self.cell_position(panel_idx, field.i, field.j);
+            let size = CELL_SIZE - 2 * CARD_INSET;
+            let class = if field.top_card.is_some() {
+                "card"
+            } else {
+                "face-down"
+            };
+            write!(
+                self.body,
+                r#"<rect class="{}" x="{}" y="{}" width="{}" height="{}" rx="6"/>"#,
+                class,
+                x + CARD_INSET,
+                y + CARD_INSET,
+                size,
+                size
+            )
+            .unwrap();
+            if let Some(card) = field.top_card {
+                write!(
+                    self.body,
+                    r#"<text class="rank" x="{}" y="{}" fill="{}">{}</text>"#,
+                    x + CELL_SIZE / 2,
+                    y + CELL_SIZE / 2,
+                    suit_color(card.suit),
+                    card_text(card)
+                )
+                .unwrap();
+            }
+            if !field.hidden_cards.is_empty() {
+                let color = if field.top_card.is_some() {
+                    ""
+                } else {
+                    r#" style="fill:#fff""#
+                };
+                write!(
+                    self.body,
+                    r#"<text class="hidden" x="{}" y="{}"{}>+{}</text>"#,
+                    x + CELL_SIZE - CARD_INSET - 4,
+                    y + CELL_SIZE - CARD_INSET - 6,
+                    color,
+                    field.hidden_cards.len()
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    // Outlines the placed cards with their index in the turn, and draws arrows to the
+    // targets of kings.
+    fn draw_placements(&mut self, panel_idx: i32, placements: &[Placement]) {
+        for (idx, placement) in placements.iter().enumerate() {
+            let (x, y) = self.cell_position(panel_idx, placement.i, placement.j);
+            write!(
+                self.body,
+                r#"<rect class="placed" x="{}" y="{}" width="{}" height="{}" rx="8"/>"#,
+                x + 1,
+                y + 1,
+                CELL_SIZE - 2,
+                CELL_SIZE - 2
+            )
+            .unwrap();
+            write!(
+                self.body,
+                r##"<circle cx="{}" cy="{}" r="8" fill="#1f77b4"/><text class="step" x="{}" y="{}">{}</text>"##,
+                x + 8,
+                y + 8,
+                x + 8,
+                y + 8,
+                idx + 1
+            )
+            .unwrap();
+        }
+        for placement in placements {
+            if let Some((tgt_i, tgt_j)) = placement.king_target {
+                let (x1, y1) = self.cell_center(panel_idx, placement.i, placement.j);
+                let (x2, y2) = self.cell_center(panel_idx, tgt_i, tgt_j);
+                write!(
+                    self.body,
+                    r#"<line class="arrow" x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                    x1, y1, x2, y2
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn draw_won(&mut self, panel_idx: i32, won: &[(i8, i8)]) {
+        for &(i, j) in won {
+            let (x, y) = self.cell_position(panel_idx, i, j);
+            write!(
+                self.body,
+                r#"<rect class="won" x="{}" y="{}" width="{}" height="{}" rx="6"/>"#,
+                x + CARD_INSET,
+                y + CARD_INSET,
+                CELL_SIZE - 2 * CARD_INSET,
+                CELL_SIZE - 2 * CARD_INSET
+            )
+            .unwrap();
+        }
+    }
+
+    fn finish(self, num_panels: i32) -> String {
+        let width = num_panels * self.panel_width() + (num_panels - 1) * PANEL_GAP;
+        let height = self.panel_height();
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+                r##"<defs><style>{style}</style><marker id="arrowhead" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="6" markerHeight="6" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 z" fill="#d62728"/></marker></defs>"##,
+                r##"<rect width="100%" height="100%" fill="#fafafa"/>"##,
+                "{body}</svg>\n"
+            ),
+            w = width,
+            h = height,
+            style = STYLE,
+            body = self.body
+        )
+    }
+}
+
+fn suit_color(suit: Suit) -> &'static str {
+    match suit {
+        Suit::Diamond | Suit::Heart => "#c0392b",
+        Suit::Spade | Suit::Club => "#222",
+    }
+}
+
+fn card_text(card: Card) -> String {
+    let (rank_char, suit_char) = card_chars(card);
+    match rank_char {
+        'T' => format!("10{}", suit_char),
+        _ => format!("{}{}", rank_char, suit_char),
+    }
+}
+
+fn coordinates_of(board: &Board) -> impl Iterator<Item = (i8, i8)> {
+    board
+        .to_fields_vec()
+        .into_iter()
+        .map(|field| (field.i, field.j))
+}
+
+impl Board {
+    /// Renders the board as a standalone SVG image.
+    ///
+    /// The `i` coordinate goes from top to bottom, and the `j` coordinate from left to right.
+    pub fn to_svg(&self) -> String {
+        let mut image = SvgImage::new(self.bbox(), false);
+        image.draw_board(0, self);
+        image.finish(1)
+    }
+
+    /// Renders a turn as a standalone SVG image, showing the board before and after the turn.
+    ///
+    /// On the board before the turn, the placed cards are marked and numbered, and the
+    /// targets of kings are shown as arrows. On the board after the turn, the fields whose
+    /// cards were won are marked.
+    ///
+    /// Only the placement of the cards is checked, see [`validate_turn()`](crate::validate_turn)
+    /// for checking the whole turn.
+    pub fn turn_to_svg(&self, turn: &PlayTurnResponse) -> Result<String, IllegalMove> {
+        let mut board = self.clone();
+        let mut placements = Vec::new();
+        let mut won = Vec::new();
+        for (card_idx, &card_to_play) in turn.0.iter().enumerate() {
+            let next_board =
+                board
+                    .play_card(card_to_play)
+                    .map_err(|err| IllegalMove::IllegalCardPlayed {
+                        card_idx,
+                        card: card_to_play.card,
+                        err,
+                    })?;
+            // Won cards are the only ones that are removed from the board
+            won.extend(coordinates_of(&board).filter(|&(i, j)| next_board.get(i, j).is_none()));
+            placements.push(Placement {
+                i: card_to_play.i,
+                j: card_to_play.j,
+                king_target: card_to_play.target_field_for_king_ability,
+            });
+            board = next_board;
+        }
+
+        let all_coordinates = coordinates_of(self).chain(coordinates_of(&board)).chain(
+            placements
+                .iter()
+                .map(|placement| (placement.i, placement.j)),
+        );
+        let area = BoundingBox::from_coordinates_iter(all_coordinates).unwrap();
+        let mut image = SvgImage::new(area, true);
+        image.draw_title(0, "Before");
+        image.draw_board(0, self);
+        image.draw_placements(0, &placements);
+        image.draw_title(1, "After");
+        image.draw_board(1, &board);
+        image.draw_won(1, &won);
+        Ok(image.finish(2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn board_svg() {
+        let board = Board::from_position_string("0,0=7♥/0,1=_(2♣Q♠)/1,0=T♦(3♥)").unwrap();
+        let svg = board.to_svg();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">7♥</text>"));
+        assert!(svg.contains(">10♦</text>"));
+        assert!(svg.contains(">+2</text>"));
+        assert_eq!(svg.matches("class=\"face-down\"").count(), 1);
+        assert_eq!(svg.matches("<text").count(), svg.matches("</text>").count());
+    }
+
+    #[test]
+    fn turn_svg() {
+        let board = Board::from_position_string("0,0=7♦/0,1=8♦/0,2=9♦/1,0=2♥/1,1=5♣").unwrap();
+        let turn = PlayTurnResponse::from_str("K♥@1,0>1,1; T♦@0,3").unwrap();
+        let svg = board.turn_to_svg(&turn).unwrap();
+        assert!(svg.contains(">Before</text>"));
+        assert_eq!(svg.matches("class=\"placed\"").count(), 2);
+        assert_eq!(svg.matches("class=\"arrow\"").count(), 1);
+        assert_eq!(svg.matches("class=\"won\"").count(), 3);
+
+        let illegal_turn = PlayTurnResponse::from_str("T♦@0,3; 2♣@5,5").unwrap();
+        assert!(matches!(
+            board.turn_to_svg(&illegal_turn),
+            Err(IllegalMove::IllegalCardPlayed { card_idx: 1, .. })
+        ));
+    }
+}
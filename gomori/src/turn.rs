@@ -1,7 +1,8 @@
 use std::collections::BTreeSet;
 
 use crate::{
-    Board, CalculatedEffects, Card, CardsSet, Field, IllegalMove, PlayTurnResponse, PlayerState,
+    Board, CalculatedEffects, Card, CardToPlay, CardsSet, Field, IllegalMove, PlayTurnResponse,
+    PlayerState, RuleSet,
 };
 
 /// Summarizes the outcome of playing a turn (i.e. playing up to five cards).
@@ -14,6 +15,17 @@ pub enum TurnOutcome {
 pub fn execute_first_turn(
     state: &mut PlayerState,
     card_to_play: Card,
+) -> Result<Board, IllegalMove> {
+    execute_first_turn_with_rules(state, card_to_play, RuleSet::STANDARD)
+}
+
+/// Like [`execute_first_turn()`], but creates a board for a variant of the rules.
+///
+/// All later turns are played according to the [rules of the board](Board::rules).
+pub fn execute_first_turn_with_rules(
+    state: &mut PlayerState,
+    card_to_play: Card,
+    rules: RuleSet,
 ) -> Result<Board, IllegalMove> {
     // Draw a new card, and validate that the card was in the hand of the player
     let mut card_found = false;
@@ -27,15 +39,17 @@ pub fn execute_first_turn(
     if !card_found {
         Err(IllegalMove::PlayedCardNotInHand)
     } else {
-        Ok(Board::new(&[Field {
+        let field = Field {
             i: 0,
             j: 0,
             top_card: Some(card_to_play),
             hidden_cards: BTreeSet::new(),
-        }]))
+        };
+        Ok(Board::try_new_with_rules(&[field], rules).expect("A single card is a valid board"))
     }
 }
 
+/// Plays a regular turn, according to the [rules of the board](Board::rules).
 pub fn execute_turn(
     state: &mut PlayerState,
     board: &mut Board,
@@ -111,3 +125,210 @@ pub fn execute_turn(
         cards_won_this_turn,
     })
 }
+
+/// A rule violation found by [`validate_turn()`].
+#[derive(Debug)]
+pub struct TurnViolation {
+    pub err: IllegalMove,
+    /// The index of the card in the turn that caused the violation, if any.
+    pub card_idx: Option<usize>,
+    /// Legal ways to play the card that caused the violation instead.
+    ///
+    /// - For an illegal placement, these are all the legal placements of the card.
+    /// - For a card that was played after the end of the combo, these are the placements
+    ///   of that card that would have continued the combo.
+    /// - For a prematurely ended combo or a skipped turn, these are the placements of the
+    ///   cards that could still have been played.
+    ///
+    /// Empty if there is no legal alternative, e.g. for a card that is not in the hand.
+    pub alternatives: Vec<CardToPlay>,
+}
+
+/// Checks a turn for all rule violations, without changing the player state or the board.
+///
+/// Unlike [`execute_turn()`], this doesn't stop at the first error. Instead, it continues
+/// with the next card where possible, so that e.g. a card that is not in the hand and
+/// also placed out of bounds results in two violations. A card whose placement is illegal
+/// leaves the board unchanged for the following cards.
+///
+/// Each violation also lists legal alternatives for the offending card, see [`TurnViolation`].
+/// Returns an empty list if and only if [`execute_turn()`] would accept the turn.
+pub fn validate_turn(
+    state: &PlayerState,
+    board: &Board,
+    action: &PlayTurnResponse,
+) -> Vec<TurnViolation> {
+    let mut violations = Vec::new();
+    let mut hand = CardsSet::from_iter(state.hand);
+    let mut board = board.clone();
+
+    if action.0.is_empty() {
+        let alternatives = playable_placements(&board, hand);
+        if !alternatives.is_empty() {
+            violations.push(TurnViolation {
+                err: IllegalMove::PlayedZeroCards,
+                card_idx: None,
+                alternatives,
+            });
+        }
+        return violations;
+    }
+    if action.0.len() > 5 {
+        violations.push(TurnViolation {
+            err: IllegalMove::PlayedMoreThanFiveCards,
+            card_idx: None,
+            alternatives: Vec::new(),
+        });
+    }
+
+    for (card_idx, &ctp) in action.0.iter().enumerate() {
+        let is_last_card = card_idx + 1 == action.0.len();
+        let in_hand = hand.contains(ctp.card);
+        if in_hand {
+            hand = hand.remove(ctp.card);
+        } else {
+            violations.push(TurnViolation {
+                err: IllegalMove::PlayedCardNotInHand,
+                card_idx: Some(card_idx),
+                alternatives: Vec::new(),
+            });
+        }
+        let board_before = board.clone();
+        match board.make(&ctp) {
+            Ok(undo) if !undo.combo && !is_last_card => {
+                let alternatives = if in_hand {
+                    board_before
+                        .placements_for_card(ctp.card)
+                        .into_iter()
+                        .filter(|alt| board_before.get(alt.i, alt.j).is_some())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                violations.push(TurnViolation {
+                    err: IllegalMove::PlayedCardAfterEndOfCombo { card_idx },
+                    card_idx: Some(card_idx),
+                    alternatives,
+                });
+            }
+            Ok(undo) if undo.combo && is_last_card => {
+                let alternatives = playable_placements(&board, hand);
+                if !alternatives.is_empty() {
+                    violations.push(TurnViolation {
+                        err: IllegalMove::PrematurelyEndedCombo { card_idx },
+                        card_idx: Some(card_idx),
+                        alternatives,
+                    });
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                violations.push(TurnViolation {
+                    err: IllegalMove::IllegalCardPlayed {
+                        card_idx,
+                        card: ctp.card,
+                        err,
+                    },
+                    card_idx: Some(card_idx),
+                    alternatives: if in_hand {
+                        board.placements_for_card(ctp.card)
+                    } else {
+                        Vec::new()
+                    },
+                });
+            }
+        }
+    }
+    violations
+}
+
+fn playable_placements(board: &Board, hand: CardsSet) -> Vec<CardToPlay> {
+    hand.into_iter()
+        .flat_map(|card| board.placements_for_card(card))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use quickcheck::quickcheck;
+
+    use super::*;
+    use crate::arbitrary::TurnInput;
+    use crate::{card, IllegalCardPlayed};
+
+    quickcheck! {
+        fn validate_agrees_with_execute(input: TurnInput, moves: Vec<(u8, i8, i8, Card)>) -> bool {
+            let board = Board::new(&input.fields);
+            // Mostly cards from the hand, on coordinates close to the existing cards
+            let turn: Vec<_> = moves
+                .into_iter()
+                .take(6)
+                .map(|(idx, i, j, other_card)| {
+                    let card = input.hand.get(usize::from(idx % 6)).copied().unwrap_or(other_card);
+                    let (i, j) = (i % 4, j % 4);
+                    CardToPlay { card, i, j, target_field_for_king_ability: Some((i, j)) }
+                })
+                .collect();
+            let mut state = PlayerState {
+                draw_pile: Vec::new(),
+                hand: input.hand,
+                cards_won: CardsSet::new(),
+            };
+            let violations = validate_turn(&state, &board, &PlayTurnResponse(turn.clone()));
+            let mut board = board;
+            violations.is_empty() == execute_turn(&mut state, &mut board, PlayTurnResponse(turn)).is_ok()
+        }
+    }
+
+    #[test]
+    fn reports_all_violations() {
+        let board = Board::new(&[Field {
+            i: 0,
+            j: 0,
+            top_card: Some(card!("7♥")),
+            hidden_cards: BTreeSet::new(),
+        }]);
+        let state = PlayerState {
+            draw_pile: Vec::new(),
+            hand: [
+                card!("2♦"),
+                card!("3♦"),
+                card!("4♦"),
+                card!("5♦"),
+                card!("6♦"),
+            ],
+            cards_won: CardsSet::new(),
+        };
+        let ctp = CardToPlay {
+            card: card!("7♣"),
+            i: 10,
+            j: 0,
+            target_field_for_king_ability: None,
+        };
+        let violations = validate_turn(&state, &board, &PlayTurnResponse(vec![ctp]));
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(
+            violations[0].err,
+            IllegalMove::PlayedCardNotInHand
+        ));
+        assert!(matches!(
+            violations[1].err,
+            IllegalMove::IllegalCardPlayed {
+                card_idx: 0,
+                err: IllegalCardPlayed::OutOfBounds,
+                ..
+            }
+        ));
+        // The 7♣ could be played on the 7♥, but it's not in the hand
+        assert!(violations[1].alternatives.is_empty());
+
+        let ctp = CardToPlay {
+            card: card!("5♦"),
+            ..ctp
+        };
+        let violations = validate_turn(&state, &board, &PlayTurnResponse(vec![ctp]));
+        assert_eq!(violations.len(), 1);
+        // The 5♦ can be played on any of the 48 free fields
+        assert_eq!(violations[0].alternatives.len(), 48);
+    }
+}
@@ -1,9 +1,20 @@
-use crate::Field;
+use std::fmt::{self, Display, Write};
 
+use crate::notation::card_chars;
+use crate::{BitBoard, Board, Card, Field, Suit};
+
+/// Draws the top cards of the fields in a box.
+///
+/// Returns an empty string if there are no fields.
 pub fn visualize_top_cards(fields: &[Field]) -> String {
+    let mut fields: Vec<&Field> = fields.iter().collect();
+    fields.sort_by_key(|field| (field.i, field.j));
+    if fields.is_empty() {
+        return String::new();
+    }
     let (mut i_min, mut i_max, mut j_min, mut j_max) =
         (fields[0].i, fields[0].i, fields[0].j, fields[0].j);
-    for field in fields {
+    for field in &fields {
         i_min = i_min.min(field.i);
         i_max = i_max.max(field.i);
         j_min = j_min.min(field.j);
@@ -55,3 +66,186 @@ pub fn visualize_top_cards(fields: &[Field]) -> String {
     result += "╯";
     result
 }
+
+/// A text rendering of a [`Board`], created by [`Board::render()`].
+///
+/// The rendering is produced by the [`Display`] impl.
+#[derive(Clone, Copy, Debug)]
+pub struct BoardRenderer<'a> {
+    board: &'a Board,
+    highlighted: Option<BitBoard>,
+    colors: bool,
+}
+
+// The width of a field in characters: Two for the top card, three for the number of hidden
+// cards, and one on either side for the highlight brackets.
+const FIELD_WIDTH: usize = 7;
+
+impl Board {
+    /// Renders the board as text, e.g. for logs or debug output.
+    ///
+    /// Every field shows its top card followed by the number of hidden cards below it, e.g.
+    /// `K♦+1`. A face-down field is shown as `##` followed by the number of its cards, e.g.
+    /// `##2`. The `i` coordinates are shown on the left, and the `j` coordinates on top:
+    ///
+    /// ```text
+    ///         0      1
+    ///     ╭──────────────╮
+    ///   0 │ 7♥     ##2   │
+    ///   1 │ K♦+1         │
+    ///     ╰──────────────╯
+    /// ```
+    ///
+    /// The returned value can be configured further before it is displayed:
+    ///
+    /// ```
+    /// # use gomori::{card, Board};
+    /// let board = Board::from_position_string("0,0=7♥/0,1=_(2♣Q♠)/1,0=K♦(3♥)").unwrap();
+    /// let locations = board.locations_for_card(card!("8♥"));
+    /// println!("{}", board.render().highlight(locations).with_colors(true));
+    /// ```
+    pub fn render(&self) -> BoardRenderer<'_> {
+        BoardRenderer {
+            board: self,
+            highlighted: None,
+            colors: false,
+        }
+    }
+}
+
+impl<'a> BoardRenderer<'a> {
+    /// Highlights the given coordinates, e.g. the result of [`Board::locations_for_card()`]
+    /// or the won cards.
+    ///
+    /// Highlighted fields are enclosed in brackets, and shown in reverse video if colors
+    /// are enabled. The rendered area is extended to include all highlighted coordinates.
+    #[must_use]
+    pub fn highlight(self, coordinates: BitBoard) -> Self {
+        Self {
+            highlighted: Some(coordinates),
+            ..self
+        }
+    }
+
+    /// Whether to color the cards by their suit with ANSI escape codes.
+    #[must_use]
+    pub fn with_colors(self, colors: bool) -> Self {
+        Self { colors, ..self }
+    }
+
+    fn write_field(&self, f: &mut fmt::Formatter<'_>, i: i8, j: i8) -> fmt::Result {
+        let highlighted = self.highlighted.is_some_and(|bb| bb.contains(i, j));
+        let (left, right) = if highlighted { ('[', ']') } else { (' ', ' ') };
+        if highlighted && self.colors {
+            write!(f, "\x1b[7m")?;
+        }
+        write!(f, "{}", left)?;
+        match self.board.get(i, j) {
+            Some(field) => match field.top_card() {
+                Some(card) => {
+                    self.write_card(f, card)?;
+                    match field.num_hidden_cards() {
+                        0 => write!(f, "   ")?,
+                        num_hidden_cards => write!(f, "{:<3}", format!("+{}", num_hidden_cards))?,
+                    }
+                }
+                None => write!(f, "##{:<3}", field.num_hidden_cards())?,
+            },
+            None => write!(f, "{:1$}", "", FIELD_WIDTH - 2)?,
+        }
+        write!(f, "{}", right)?;
+        if highlighted && self.colors {
+            write!(f, "\x1b[27m")?;
+        }
+        Ok(())
+    }
+
+    fn write_card(&self, f: &mut fmt::Formatter<'_>, card: Card) -> fmt::Result {
+        let (rank_char, suit_char) = card_chars(card);
+        if !self.colors {
+            return write!(f, "{}{}", rank_char, suit_char);
+        }
+        // The colors of a four-color deck, with the default color instead of black
+        let color = match card.suit {
+            Suit::Diamond => "34",
+            Suit::Heart => "31",
+            Suit::Spade => "39",
+            Suit::Club => "32",
+        };
+        write!(f, "\x1b[{}m{}{}\x1b[39m", color, rank_char, suit_char)
+    }
+}
+
+impl Display for BoardRenderer<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut area = self.board.bbox();
+        for (i, j) in self.highlighted.into_iter().flatten() {
+            area.update(i, j);
+        }
+        let mut border = String::new();
+        for _ in area.j_min..=area.j_max {
+            border.push_str(&"─".repeat(FIELD_WIDTH));
+        }
+
+        write!(f, "     ")?;
+        for j in area.j_min..=area.j_max {
+            write!(f, "{:^1$}", j, FIELD_WIDTH)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "    ╭{}╮", border)?;
+        for i in area.i_min..=area.i_max {
+            write!(f, "{:>3} │", i)?;
+            for j in area.j_min..=area.j_max {
+                self.write_field(f, i, j)?;
+            }
+            f.write_char('│')?;
+            writeln!(f)?;
+        }
+        write!(f, "    ╰{}╯", border)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card;
+
+    #[test]
+    fn render_board() {
+        let board = Board::from_position_string("0,0=7♥/0,1=_(2♣Q♠)/1,0=K♦(3♥)").unwrap();
+        let expected = [
+            "        0      1   ",
+            "    ╭──────────────╮",
+            "  0 │ 7♥     ##2   │",
+            "  1 │ K♦+1         │",
+            "    ╰──────────────╯",
+        ];
+        assert_eq!(board.render().to_string(), expected.join("\n"));
+
+        let highlighted = board.locations_for_card(card!("8♥"));
+        let rendered = board.render().highlight(highlighted).to_string();
+        assert!(rendered.contains(" -2 │"));
+        assert!(rendered.contains(" 7♥    [##2  ]"));
+        assert_eq!(
+            rendered.matches('[').count(),
+            highlighted.num_entries() as usize
+        );
+
+        let colored = board.render().with_colors(true).to_string();
+        assert!(colored.contains("\x1b[31m7♥\x1b[39m"));
+
+        // A single face-down card
+        let board = Board::from_position_string("0,0=_(2♣)/0,1=3♣").unwrap();
+        assert!(board.render().to_string().contains("  0 │ ##1    3♣    │"));
+    }
+
+    #[test]
+    fn visualize_unsorted_and_empty_fields() {
+        let board = Board::from_position_string("0,0=7♥/1,1=2♣").unwrap();
+        let mut fields = board.to_fields_vec();
+        let sorted = visualize_top_cards(&fields);
+        fields.reverse();
+        assert_eq!(visualize_top_cards(&fields), sorted);
+        assert_eq!(visualize_top_cards(&[]), "");
+    }
+}
@@ -1,8 +1,5 @@
 use crate::Bot;
-use gomori::{
-    Board, Card, CardsSet, Color, CompactField, Field, PlayTurnResponse, BLACK_CARDS_SET,
-    RED_CARDS_SET,
-};
+use gomori::{Board, Card, CardsSet, Color, PlayTurnResponse, BLACK_CARDS_SET, RED_CARDS_SET};
 
 /// Information about the cards in the game, derived from
 /// observing all played cards.
@@ -86,18 +83,18 @@ impl<T: HasCardCounter + Bot> Bot for CardCountingWrapper<T> {
     fn play_turn(
         &mut self,
         cards: [Card; 5],
-        fields: Vec<Field>,
+        mut board: Board,
         cards_won_by_opponent: CardsSet,
     ) -> PlayTurnResponse {
         self.bot.get_counter().draw_pile &= !CardsSet::from_iter(cards);
         self.bot.get_counter().cards_won_opponent |= cards_won_by_opponent;
         self.bot.get_counter().available_cards_opponent &= !cards_won_by_opponent;
-        for field in &fields {
-            self.bot.get_counter().available_cards_opponent &=
-                !CompactField::from(field).all_cards();
+        for (_, _, field) in board.iter() {
+            self.bot.get_counter().available_cards_opponent &= !field.all_cards();
         }
-        let mut board = Board::new(&fields);
-        let response = self.bot.play_turn(cards, fields, cards_won_by_opponent);
+        let response = self
+            .bot
+            .play_turn(cards, board.clone(), cards_won_by_opponent);
         for &card_to_play in &response.0 {
             if let Ok(effects) = board.calculate(card_to_play) {
                 self.bot.get_counter().cards_won_self |= effects.cards_won;
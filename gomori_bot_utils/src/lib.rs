@@ -1,18 +1,65 @@
 mod card_counting;
 pub use card_counting::*;
 
-use gomori::{Card, CardsSet, Color, Field, Okay, PlayTurnResponse, Request};
+use anyhow::Context;
+use gomori::{
+    features, read_frame, write_frame, BinaryMessage, Board, Card, CardsSet, Color, GameOverReason,
+    HelloResponse, Okay, OpponentTurn, PlayTurnResponse, Request,
+};
+use serde::Serialize;
+
+/// The protocol extensions that [`Bot::run()`] knows how to handle.
+pub const SUPPORTED_FEATURES: &[&str] = &[
+    features::GAME_OVER,
+    features::OPPONENT_TURN,
+    features::BINARY_ENCODING,
+];
 
 /// A trait to simplify writing bots.
 pub trait Bot {
+    /// The name that is reported to the judge. Defaults to the type name.
+    fn name(&self) -> String {
+        String::from(std::any::type_name::<Self>())
+    }
+    /// The version that is reported to the judge.
+    fn version(&self) -> String {
+        String::from("unknown")
+    }
+    /// Whether to accept the binary encoding of the protocol if the judge offers it, which is
+    /// faster but not human-readable. Defaults to `false`.
+    fn use_binary_encoding(&self) -> bool {
+        false
+    }
+
     fn new_game(&mut self, color: Color);
     fn play_first_turn(&mut self, cards: [Card; 5]) -> Card;
+    /// Called with the board that is built from the fields of the request, which
+    /// [`run()`](Bot::run) has already checked to be valid.
     fn play_turn(
         &mut self,
         cards: [Card; 5],
-        fields: Vec<Field>,
+        board: Board,
         cards_won_by_opponent: CardsSet,
     ) -> PlayTurnResponse;
+    /// Called with the opponent's previous turn, right before
+    /// [`play_turn()`](Bot::play_turn). Does nothing by default.
+    fn opponent_turn(&mut self, opponent_turn: OpponentTurn) {
+        let _ = opponent_turn;
+    }
+    /// Called after every game with its result. Does nothing by default.
+    ///
+    /// `winner` is `None` for a tie, and `board` is `None` if the first turn was not played
+    /// successfully.
+    fn game_over(
+        &mut self,
+        winner: Option<Color>,
+        cards_won: CardsSet,
+        cards_won_by_opponent: CardsSet,
+        board: Option<Board>,
+        reason: GameOverReason,
+    ) {
+        let _ = (winner, cards_won, cards_won_by_opponent, board, reason);
+    }
 
     fn run(&mut self) -> anyhow::Result<()> {
         // Communication happens through stdin/stdout.
@@ -20,40 +67,110 @@ pub trait Bot {
         let mut stdin = std::io::stdin().lock();
         let mut stdout = std::io::stdout().lock();
         let mut buf = String::new();
+        let mut binary_buf = Vec::new();
+        // Switched on by the Hello request
+        let mut binary = false;
 
         loop {
-            // Read the next line into buf
-            buf.clear(); // because stdin.read_line() appends to the buffer
-            use std::io::BufRead;
-            let num_bytes_read = stdin.read_line(&mut buf)?;
-            if num_bytes_read == 0 {
-                // 0 bytes read means EOF - the judge has exited.
-                break Ok(());
-            }
-
-            let req = serde_json::from_str::<Request>(buf.trim_end())?;
+            let req = if binary {
+                if !read_frame(&mut stdin, &mut binary_buf)? {
+                    // The judge has exited
+                    break Ok(());
+                }
+                Request::decode(&binary_buf)?
+            } else {
+                // Read the next line into buf
+                buf.clear(); // because stdin.read_line() appends to the buffer
+                use std::io::BufRead;
+                let num_bytes_read = stdin.read_line(&mut buf)?;
+                if num_bytes_read == 0 {
+                    // 0 bytes read means EOF - the judge has exited.
+                    break Ok(());
+                }
+                serde_json::from_str::<Request>(buf.trim_end())?
+            };
 
             match req {
+                Request::Hello { features, .. } => {
+                    let use_binary_encoding = self.use_binary_encoding();
+                    let response = HelloResponse {
+                        name: self.name(),
+                        version: self.version(),
+                        features: features
+                            .into_iter()
+                            .filter(|feature| SUPPORTED_FEATURES.contains(&feature.as_str()))
+                            .filter(|feature| {
+                                use_binary_encoding || feature != features::BINARY_ENCODING
+                            })
+                            .collect(),
+                    };
+                    // The response is always JSON
+                    respond(&mut stdout, &response, false)?;
+                    binary = response
+                        .features
+                        .iter()
+                        .any(|feature| feature == features::BINARY_ENCODING);
+                }
                 Request::NewGame { color } => {
                     self.new_game(color);
-                    serde_json::to_writer(&mut stdout, &Okay())?;
+                    respond(&mut stdout, &Okay(), binary)?;
                 }
                 Request::PlayFirstTurn { cards } => {
-                    serde_json::to_writer(&mut stdout, &self.play_first_turn(cards))?
+                    respond(&mut stdout, &self.play_first_turn(cards), binary)?;
                 }
                 Request::PlayTurn {
                     cards,
                     fields,
                     cards_won_by_opponent,
-                } => serde_json::to_writer(
-                    &mut stdout,
-                    &self.play_turn(cards, fields, CardsSet::from_iter(cards_won_by_opponent)),
-                )?,
+                    opponent_turn,
+                } => {
+                    let board = Board::try_new(&fields).context("Received an invalid board")?;
+                    if let Some(opponent_turn) = opponent_turn {
+                        self.opponent_turn(opponent_turn);
+                    }
+                    let response =
+                        self.play_turn(cards, board, CardsSet::from_iter(cards_won_by_opponent));
+                    respond(&mut stdout, &response, binary)?;
+                }
+                Request::GameOver {
+                    winner,
+                    cards_won,
+                    cards_won_by_opponent,
+                    fields,
+                    reason,
+                } => {
+                    let board = if fields.is_empty() {
+                        None
+                    } else {
+                        Some(Board::try_new(&fields).context("Received an invalid board")?)
+                    };
+                    self.game_over(
+                        winner,
+                        CardsSet::from_iter(cards_won),
+                        CardsSet::from_iter(cards_won_by_opponent),
+                        board,
+                        reason,
+                    );
+                    respond(&mut stdout, &Okay(), binary)?;
+                }
                 Request::Bye => break Ok(()),
             }
-            use std::io::Write;
-            writeln!(stdout)?;
-            stdout.flush()?;
         }
     }
 }
+
+// Writes a response in the encoding that was agreed on.
+fn respond<T: Serialize + BinaryMessage>(
+    stdout: &mut impl std::io::Write,
+    response: &T,
+    binary: bool,
+) -> anyhow::Result<()> {
+    if binary {
+        write_frame(stdout, response)?;
+    } else {
+        serde_json::to_writer(&mut *stdout, response)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
@@ -1,15 +1,16 @@
-use std::cmp::Ordering;
 use std::collections::BTreeSet;
 
 use gomori::{
-    execute_first_turn, execute_turn, Card, CardsSet, Color, IllegalMove, Okay, PlayTurnResponse,
-    Request, TurnOutcome,
+    features, Card, CardsSet, Color, Game, GameOverReason, GameRecord, GameRecordResult,
+    IllegalMove, Okay, OpponentTurn, PlayTurnResponse, PlayerRecord, PlayerState, Request,
+    TurnOutcome, TurnRecord,
 };
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use tracing::{debug, trace};
 
-use crate::player::{Player, PlayerWithGameState};
+use crate::player::Player;
 use crate::recording::Recorder;
 
 pub enum GameResult {
@@ -26,101 +27,184 @@ pub fn play_game(
     player_2: &mut Player,
     recorder: &mut Option<Recorder>,
 ) -> anyhow::Result<GameResult> {
+    // Every game gets its own seed, so that it can be set up again from the game record
+    let seed: u64 = rng.gen();
+    let mut rng = StdRng::seed_from_u64(seed);
+
     // Assign one bot the red cards and the other the black cards randomly
-    let [player_1_color, player_2_color] = {
+    let colors @ [player_1_color, player_2_color] = {
         let mut arr = [Color::Red, Color::Black];
-        arr.shuffle(rng);
+        arr.shuffle(&mut rng);
         arr
     };
 
-    // Bundle everything up in a PlayerWithGameState struct, which tracks the player's state during this game
-    let mut players = [
-        PlayerWithGameState::new(player_1, player_1_color, rng),
-        PlayerWithGameState::new(player_2, player_2_color, rng),
+    // Deal the cards
+    let player_states = [
+        PlayerState::new(player_1_color, &mut rng),
+        PlayerState::new(player_2_color, &mut rng),
     ];
+    let mut players = [player_1, player_2];
+    let player_records = [0, 1].map(|player_idx| PlayerRecord {
+        name: players[player_idx].name.clone(),
+        color: colors[player_idx],
+        hand: player_states[player_idx].hand,
+        draw_pile: player_states[player_idx].draw_pile.clone(),
+    });
 
     // Inform the players about the new game, so that they can reset their state
-    let _: Okay = players[0].perform_request(
-        recorder,
-        &Request::NewGame {
-            color: player_1_color,
-        },
-    )?;
-    let _: Okay = players[1].perform_request(
-        recorder,
-        &Request::NewGame {
-            color: player_2_color,
-        },
-    )?;
+    let _: Okay = players[0].perform_request(&Request::NewGame {
+        color: player_1_color,
+    })?;
+    let _: Okay = players[1].perform_request(&Request::NewGame {
+        color: player_2_color,
+    })?;
 
     // Randomly pick a starting player
-    let mut current_player_idx = if rng.gen::<bool>() { 1 } else { 0 };
+    let starting_player_idx = if rng.gen::<bool>() { 1 } else { 0 };
+    let mut game = Game::from_player_states(player_states, starting_player_idx);
 
     // Play the first turn. This one is special.
     let req = Request::PlayFirstTurn {
-        cards: players[current_player_idx].state.hand,
+        cards: game.player_state(starting_player_idx).hand,
     };
-    let card: Card = players[current_player_idx].perform_request(recorder, &req)?;
-    let mut board = match execute_first_turn(&mut players[current_player_idx].state, card) {
-        Ok(board) => board,
-        Err(err) => {
-            return Ok(GameResult::IllegalMoveByPlayer {
-                player_idx: current_player_idx,
+    let first_turn: Card = players[starting_player_idx].perform_request(&req)?;
+    let mut turns = Vec::new();
+    let (game_result, reason) = match game.apply_first_turn(first_turn) {
+        Ok(()) => play_turns(&mut game, &mut players, first_turn, &mut turns)?,
+        Err(err) => (
+            GameResult::IllegalMoveByPlayer {
+                player_idx: starting_player_idx,
                 err,
-            })
-        }
+            },
+            GameOverReason::IllegalMove,
+        ),
+    };
+
+    // Tell the players how the game ended, if they want to know
+    let winner = match game_result {
+        GameResult::WonByPlayer { player_idx } => Some(colors[player_idx]),
+        GameResult::Tie => None,
+        GameResult::IllegalMoveByPlayer { player_idx, .. } => Some(colors[1 - player_idx]),
     };
+    let fields = game
+        .board()
+        .map(|board| board.to_fields_vec())
+        .unwrap_or_default();
+    for (player_idx, player) in players.iter_mut().enumerate() {
+        if player.supports(features::GAME_OVER) {
+            let _: Okay = player.perform_request(&Request::GameOver {
+                winner,
+                cards_won: BTreeSet::from_iter(game.player_state(player_idx).cards_won),
+                cards_won_by_opponent: BTreeSet::from_iter(
+                    game.player_state(1 - player_idx).cards_won,
+                ),
+                fields: fields.clone(),
+                reason,
+            })?;
+        }
+    }
+
+    if let Some(rec) = recorder {
+        rec.write_game_record(&GameRecord {
+            players: player_records,
+            seed: Some(seed),
+            starting_player_idx,
+            first_turn,
+            turns,
+            result: match game_result {
+                GameResult::WonByPlayer { player_idx } => {
+                    GameRecordResult::WonByPlayer { player_idx }
+                }
+                GameResult::Tie => GameRecordResult::Tie,
+                GameResult::IllegalMoveByPlayer { player_idx, .. } => {
+                    GameRecordResult::IllegalMoveByPlayer { player_idx }
+                }
+            },
+            scores: [
+                game.player_state(0).cards_won.len(),
+                game.player_state(1).cards_won.len(),
+            ],
+        })?;
+    }
+
+    Ok(game_result)
+}
 
-    let mut turn_skipped = false;
+// Plays all turns after the first one, and records them.
+fn play_turns(
+    game: &mut Game,
+    players: &mut [&mut Player; 2],
+    first_turn: Card,
+    turns: &mut Vec<TurnRecord>,
+) -> anyhow::Result<(GameResult, GameOverReason)> {
     let mut cards_won_by_opponent = CardsSet::new();
-    loop {
-        // eprintln!("{}", board);
-        current_player_idx = 1 - current_player_idx;
-        let current_player = &mut players[current_player_idx];
+    let mut opponent_turn = OpponentTurn::FirstTurn { card: first_turn };
+    let mut reason = GameOverReason::BothPlayersSkipped;
+    while !game.is_over() {
+        let current_player_idx = game.current_player_idx();
         let req = Request::PlayTurn {
-            cards: current_player.state.hand,
-            fields: board.to_fields_vec(),
+            cards: game.player_state(current_player_idx).hand,
+            fields: game.board().unwrap().to_fields_vec(),
             cards_won_by_opponent: BTreeSet::from_iter(cards_won_by_opponent),
+            opponent_turn: players[current_player_idx]
+                .supports(features::OPPONENT_TURN)
+                .then(|| opponent_turn.clone()),
         };
-        let action: PlayTurnResponse = current_player.perform_request(recorder, &req)?;
-        match execute_turn(&mut current_player.state, &mut board, action) {
+        let action: PlayTurnResponse = players[current_player_idx].perform_request(&req)?;
+        trace!(player_idx = current_player_idx, turn = %action);
+        opponent_turn = if action.0.is_empty() {
+            OpponentTurn::Skipped
+        } else {
+            OpponentTurn::Played {
+                cards_to_play: action.clone(),
+            }
+        };
+        let outcome = game.apply_turn(action.clone());
+        let board = game.board().unwrap().render();
+        match outcome {
+            Ok(_) => trace!("Board after the turn:\n{}", board),
+            // The board is left unchanged by an illegal turn
+            Err(_) => debug!("Board before the illegal turn:\n{}", board),
+        }
+        turns.push(TurnRecord {
+            player_idx: current_player_idx,
+            action,
+            fields: outcome
+                .is_ok()
+                .then(|| game.board().unwrap().to_fields_vec()),
+        });
+        match outcome {
             Ok(TurnOutcome::Normal {
                 cards_won_this_turn,
             }) => {
-                turn_skipped = false;
                 cards_won_by_opponent = cards_won_this_turn;
             }
-            Ok(TurnOutcome::GameEnded) => {
-                break;
-            }
             Ok(TurnOutcome::Skipped) => {
                 cards_won_by_opponent = CardsSet::new();
-                if turn_skipped {
-                    break; // When both players couldn't play a card, the game ends
-                } else {
-                    turn_skipped = true;
-                }
+            }
+            Ok(TurnOutcome::GameEnded) => {
+                cards_won_by_opponent = CardsSet::new();
+                reason = GameOverReason::DrawPileEmpty;
             }
             Err(err) => {
-                return Ok(GameResult::IllegalMoveByPlayer {
-                    player_idx: current_player_idx,
-                    err,
-                })
+                return Ok((
+                    GameResult::IllegalMoveByPlayer {
+                        player_idx: current_player_idx,
+                        err,
+                    },
+                    GameOverReason::IllegalMove,
+                ))
             }
         };
     }
 
-    if let Some(rec) = recorder {
-        rec.write_game_recording()?;
-    }
-
     // Report who won
-    let num_cards_0 = players[0].state.cards_won.len();
-    let num_cards_1 = players[1].state.cards_won.len();
-    let game_result = match num_cards_0.cmp(&num_cards_1) {
-        Ordering::Less => GameResult::WonByPlayer { player_idx: 1 },
-        Ordering::Equal => GameResult::Tie,
-        Ordering::Greater => GameResult::WonByPlayer { player_idx: 0 },
+    let game_result = match game.result() {
+        Some(gomori::GameResult::WonByPlayer { player_idx }) => {
+            GameResult::WonByPlayer { player_idx }
+        }
+        Some(gomori::GameResult::Tie) => GameResult::Tie,
+        None => unreachable!("The game loop only exits when the game is over"),
     };
-    Ok(game_result)
+    Ok((game_result, reason))
 }
@@ -29,7 +29,7 @@ struct Args {
     #[arg(short, long, default_value_t = false)]
     stop_on_illegal_move: bool,
 
-    /// Record the game's interactions as JSON files into this directory
+    /// Record the games as JSON files into this directory
     #[arg(short, long)]
     record_games_to_directory: Option<PathBuf>,
 
@@ -1,23 +1,42 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
-use std::process::{ChildStdin, ChildStdout, Command, Stdio};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
 
 use anyhow::Context;
-use gomori::{Color, PlayerState, Request};
-use rand::rngs::StdRng;
+use gomori::{
+    features, read_frame, write_frame, BinaryMessage, HelloResponse, Request, PROTOCOL_VERSION,
+};
 use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
-use crate::recording::Recorder;
+/// The protocol extensions that the judge offers in the `Hello` request.
+pub const FEATURES: &[&str] = &[
+    features::GAME_OVER,
+    features::OPPONENT_TURN,
+    features::BINARY_ENCODING,
+];
+
+/// How long to wait for the answer to the `Hello` request before falling back to protocol
+/// version 1.
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct Player {
     pub name: String,
+    /// The bot's answer to the `Hello` request, or `None` if it only speaks protocol version 1.
+    pub hello: Option<HelloResponse>,
+    child: Child,
     stdin: ChildStdin,
     stdout: BufReader<ChildStdout>,
     // A re-usable buffer for IO.
     // Should always be empty before and after perform_request().
     buf: String,
+    // Same for the binary encoding, which is used instead of JSON if the bot accepted it.
+    binary: bool,
+    binary_buf: Vec<u8>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -41,15 +60,44 @@ impl PlayerConfig {
     }
 }
 
-pub struct PlayerWithGameState<'a> {
-    pub player: &'a mut Player,
-    pub state: PlayerState,
-}
-
 impl Player {
     pub fn new(path: &Path) -> anyhow::Result<Self> {
         let config = PlayerConfig::load(path)?;
-        let child_proc = Command::new(&config.cmd[0])
+        let mut player = Self::spawn(&config)?;
+        match player.handshake() {
+            Ok(mut hello) => {
+                hello
+                    .features
+                    .retain(|feature| FEATURES.contains(&feature.as_str()));
+                info!(
+                    player = &player.name,
+                    bot = hello.name,
+                    version = hello.version,
+                    features = ?hello.features,
+                    "Handshake completed"
+                );
+                player.binary = hello
+                    .features
+                    .iter()
+                    .any(|feature| feature == features::BINARY_ENCODING);
+                player.hello = Some(hello);
+            }
+            Err(err) => {
+                // Bots that predate the handshake usually exit on an unknown request, so
+                // start over with a fresh process
+                info!(
+                    player = &player.name,
+                    "Falling back to protocol version 1: {:#}", err
+                );
+                player.kill();
+                player = Self::spawn(&config)?;
+            }
+        }
+        Ok(player)
+    }
+
+    fn spawn(config: &PlayerConfig) -> anyhow::Result<Self> {
+        let mut child_proc = Command::new(&config.cmd[0])
             .args(&config.cmd[1..])
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -58,52 +106,113 @@ impl Player {
         info!(cmd = ?config.cmd, "Spawned child process");
 
         Ok(Self {
-            name: config.nick,
-            stdin: child_proc.stdin.expect("Could not access stdin"),
-            stdout: BufReader::new(child_proc.stdout.expect("Could not access stdout")),
+            name: config.nick.clone(),
+            hello: None,
+            stdin: child_proc.stdin.take().expect("Could not access stdin"),
+            stdout: BufReader::new(child_proc.stdout.take().expect("Could not access stdout")),
+            child: child_proc,
             buf: String::new(),
+            binary: false,
+            binary_buf: Vec::new(),
         })
     }
-}
 
-impl<'a> PlayerWithGameState<'a> {
-    pub fn new(player: &'a mut Player, color: Color, rng: &mut StdRng) -> Self {
-        Self {
-            player,
-            state: PlayerState::new(color, rng),
-        }
+    // Like perform_request(), but gives up after HELLO_TIMEOUT, since bots that predate the
+    // handshake may ignore the request instead of exiting.
+    fn handshake(&mut self) -> anyhow::Result<HelloResponse> {
+        let req = Request::Hello {
+            protocol_version: PROTOCOL_VERSION,
+            features: FEATURES
+                .iter()
+                .map(|&feature| String::from(feature))
+                .collect(),
+        };
+        let mut req_json = serde_json::to_string(&req)?;
+        trace!(name: "Sending request", player = &self.name, request = %req_json);
+        req_json.push('\n');
+        self.stdin
+            .write_all(req_json.as_bytes())
+            .context("Could not send request")?;
+        self.stdin.flush()?;
+
+        let Self { child, stdout, .. } = self;
+        let line = thread::scope(|scope| {
+            let (sender, receiver) = mpsc::channel();
+            scope.spawn(move || {
+                let mut line = String::new();
+                let _ = sender.send(stdout.read_line(&mut line).map(|_| line));
+            });
+            match receiver.recv_timeout(HELLO_TIMEOUT) {
+                Ok(result) => Ok(result?),
+                Err(_) => {
+                    // Unblocks the reading thread
+                    let _ = child.kill();
+                    anyhow::bail!("No response within {:?}", HELLO_TIMEOUT)
+                }
+            }
+        })?;
+        let serialized_response = line.trim_end();
+        let response =
+            serde_json::from_str::<HelloResponse>(serialized_response).with_context(|| {
+                format!("Could not parse response '{}' as JSON", serialized_response)
+            })?;
+        trace!(name: "Recieved response", player = &self.name, response = %serialized_response);
+        Ok(response)
+    }
+
+    fn kill(&mut self) {
+        // The process may already have exited, which is fine
+        let _ = self.child.kill();
+        let _ = self.child.wait();
     }
 
-    pub fn perform_request<T: serde::de::DeserializeOwned>(
+    /// Whether the bot accepted the given protocol extension in the handshake.
+    pub fn supports(&self, feature: &str) -> bool {
+        self.hello
+            .as_ref()
+            .is_some_and(|hello| hello.features.iter().any(|f| f == feature))
+    }
+
+    pub fn perform_request<T: serde::de::DeserializeOwned + BinaryMessage + std::fmt::Debug>(
         &mut self,
-        recorder: &mut Option<Recorder>,
         req: &Request,
     ) -> anyhow::Result<T> {
         let mut inner = || -> anyhow::Result<T> {
+            if self.binary {
+                return self.perform_binary_request(req);
+            }
             let mut req_json = serde_json::to_string(req)?;
-            trace!(name: "Sending request", player = &self.player.name, request = %req_json);
+            trace!(name: "Sending request", player = &self.name, request = %req_json);
             req_json.push('\n');
-            self.player
-                .stdin
+            self.stdin
                 .write_all(req_json.as_bytes())
                 .context("Could not send request")?;
-            self.player.stdin.flush()?;
-            self.player.buf.clear();
-            self.player.stdout.read_line(&mut self.player.buf)?;
-            let serialized_response = self.player.buf.trim_end();
+            self.stdin.flush()?;
+            self.buf.clear();
+            self.stdout.read_line(&mut self.buf)?;
+            let serialized_response = self.buf.trim_end();
             let response = serde_json::from_str::<T>(serialized_response).with_context(|| {
                 format!("Could not parse response '{}' as JSON", serialized_response)
             })?;
-            trace!(name: "Recieved response", player = &self.player.name, response = %serialized_response);
-            if let Some(recorder) = recorder {
-                recorder.store_request(
-                    &self.player.name,
-                    req_json,
-                    String::from(serialized_response),
-                );
-            }
+            trace!(name: "Recieved response", player = &self.name, response = %serialized_response);
             Ok(response)
         };
-        inner().with_context(|| format!("Failed to make a request to '{}'", self.player.name))
+        inner().with_context(|| format!("Failed to make a request to '{}'", self.name))
+    }
+
+    fn perform_binary_request<T: BinaryMessage + std::fmt::Debug>(
+        &mut self,
+        req: &Request,
+    ) -> anyhow::Result<T> {
+        trace!(name: "Sending request", player = &self.name, request = ?req);
+        write_frame(&mut self.stdin, req).context("Could not send request")?;
+        if !read_frame(&mut self.stdout, &mut self.binary_buf)? {
+            anyhow::bail!("The bot closed its output");
+        }
+        let response = T::decode(&self.binary_buf)
+            .with_context(|| format!("Could not decode binary response {:?}", self.binary_buf))?;
+        self.binary_buf.clear();
+        trace!(name: "Recieved response", player = &self.name, response = ?response);
+        Ok(response)
     }
 }